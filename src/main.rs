@@ -1,14 +1,28 @@
+#![allow(dead_code)]
+
+mod flow;
+mod random;
+
+use std::cell::OnceCell;
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BinaryHeap, HashSet, VecDeque};
+use std::fmt::{self, Write};
+use std::ops::{ControlFlow, Deref};
 use std::vec;
 
 /// 有向グラフの辺りスト
 /// 各辺の始点と終点をそれぞれ別の配列で管理する
 /// 使用する領域は2mである
 /// ただし配列のindexを1から初めている
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 struct EdgeList {
     tail: vec::Vec<usize>,
     head: vec::Vec<usize>,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 struct DirectedGraph {
     edge_first: Vec<usize>,
     edge_next: Vec<usize>,
@@ -19,11 +33,43 @@ struct DirectedGraph {
 /// pre_label[v] 頂点 v を最初に訪問した順番
 /// post_label[v] 頂点 v の探索が完了した順番
 /// 頂点vから出る辺の全てがを調べ尽くした順番のこと
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 struct DfsTime {
     pre_label: Vec<usize>,
     post_label: Vec<usize>,
 }
 
+/// グラフの構築に失敗した理由
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum GraphError {
+    /// 辺edgeの端点vertexが1..=nの範囲にない
+    VertexOutOfRange { edge: usize, vertex: usize },
+    /// tailまたはheadの長さがm+1でない
+    LengthMismatch,
+    /// tail[0]またはhead[0]のダミーが0でない
+    DummyNotZero,
+    /// 頂点数または辺数が、配列の添字として扱える範囲を超えている
+    CapacityOverflow,
+}
+
+impl fmt::Display for GraphError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GraphError::VertexOutOfRange { edge, vertex } => {
+                write!(f, "edge {edge} has vertex {vertex} out of range")
+            }
+            GraphError::LengthMismatch => write!(f, "tail/head length does not match m + 1"),
+            GraphError::DummyNotZero => write!(f, "dummy entry at index 0 is not 0"),
+            GraphError::CapacityOverflow => {
+                write!(f, "vertex or edge count exceeds index capacity")
+            }
+        }
+    }
+}
+
+impl std::error::Error for GraphError {}
+
 /// edge_firstの添字は頂点の番号
 /// edge_first[v]
 /// 頂点vを始点とする最初の辺の番号
@@ -54,6 +100,8 @@ struct DfsTime {
 /// edge_firstとedge_nextは辺IDを鎖で繋ぐイメージ
 /// n: 頂点数
 /// m: 辺数
+/// n = 0 のときは全ての配列がダミーだけの vec![0] になり、
+/// m = 0 のときはedge_first, rev_edge_firstが全て0(どの頂点からも辺が出ない)になる
 fn dicomp_incidence_list_construct(graph: &EdgeList, n: usize, m: usize) -> DirectedGraph {
     let mut edge_first = vec![0; n + 1];
     let mut edge_next = vec![0; m + 1];
@@ -84,11 +132,106 @@ fn dicomp_incidence_list_construct(graph: &EdgeList, n: usize, m: usize) -> Dire
     }
 }
 
+/// 接続リストの鎖をたどって辺の番号を順に返すイテレータ
+/// first[v]から始めてnext[a]をたどり、0になったら終わる
+struct EdgeChain<'a> {
+    next: &'a [usize],
+    a: usize,
+}
+
+impl Iterator for EdgeChain<'_> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        if self.a == 0 {
+            return None;
+        }
+        let a = self.a;
+        self.a = self.next[a];
+        Some(a)
+    }
+}
+
+/// 頂点vを始点とする辺を返すイテレータ
+type OutEdges<'a> = EdgeChain<'a>;
+/// 頂点vを終点とする辺を返すイテレータ
+type InEdges<'a> = EdgeChain<'a>;
+
+/// 頂点vを始点とする辺の番号を edge_first[v], edge_next[a], ... の順に返す
+fn out_edges(graph: &DirectedGraph, v: usize) -> OutEdges<'_> {
+    EdgeChain {
+        next: &graph.edge_next,
+        a: graph.edge_first[v],
+    }
+}
+
+/// 頂点vを終点とする辺の番号を rev_edge_first[v], rev_edge_next[a], ... の順に返す
+fn in_edges(graph: &DirectedGraph, v: usize) -> InEdges<'_> {
+    EdgeChain {
+        next: &graph.rev_edge_next,
+        a: graph.rev_edge_first[v],
+    }
+}
+
+/// Vec<usize>として確保できる長さの上限(確保する大きさはisize::MAXバイトまで)
+const MAX_ARRAY_LEN: usize = isize::MAX as usize / std::mem::size_of::<usize>();
+
+/// 辺リストがdicomp_incidence_list_constructの前提を満たしているか調べる
+/// tail, headの長さがm+1で、先頭のダミーが0で、全ての端点が1..=nにあること
+/// n + 1 や m + 1 がMAX_ARRAY_LENを超えるときは、配列を確保できないのでCapacityOverflow
+/// n: 頂点数
+/// m: 辺数
+fn validate(graph: &EdgeList, n: usize, m: usize) -> Result<(), GraphError> {
+    if n >= MAX_ARRAY_LEN || m >= MAX_ARRAY_LEN {
+        return Err(GraphError::CapacityOverflow);
+    }
+    let len = m + 1;
+    if graph.tail.len() != len || graph.head.len() != len {
+        return Err(GraphError::LengthMismatch);
+    }
+    if graph.tail[0] != 0 || graph.head[0] != 0 {
+        return Err(GraphError::DummyNotZero);
+    }
+    for a in 1..=m {
+        for vertex in [graph.tail[a], graph.head[a]] {
+            if vertex == 0 || vertex > n {
+                return Err(GraphError::VertexOutOfRange { edge: a, vertex });
+            }
+        }
+    }
+    Ok(())
+}
+
+/// dicomp_incidence_list_constructの入力を検査してから構築する
+/// 信頼できない入力から作った辺リストでもpanicせずにエラーを返す
+/// 検査を通った入力では、配列の長さ(n + 1, m + 1)がMAX_ARRAY_LEN以下になり、構築中の添字も桁あふれしない
+/// (メモリが足りないときの確保の失敗は検査しない)
+/// n: 頂点数
+/// m: 辺数
+fn try_construct(graph: &EdgeList, n: usize, m: usize) -> Result<DirectedGraph, GraphError> {
+    validate(graph, n, m)?;
+    Ok(dicomp_incidence_list_construct(graph, n, m))
+}
+
+/// 接続リストから分かる頂点数(edge_firstの長さからダミーの分を引いたもの)
+fn vertex_count(graph: &DirectedGraph) -> usize {
+    graph.edge_first.len() - 1
+}
+
+/// 辺リストから分かる辺数(tailの長さからダミーの分を引いたもの)
+fn edge_count(edge: &EdgeList) -> usize {
+    edge.tail.len() - 1
+}
+
 /// 深さ優先探索
 /// 頂点vを始点として探索を行う
 /// n: 頂点数
-/// v: 探索の始点
+/// v: 探索の始点(1..=nであること)
 fn dfs(edge: &EdgeList, graph: &DirectedGraph, n: usize, v: usize) -> DfsTime {
+    debug_assert!(
+        (1..=n).contains(&v),
+        "start vertex {v} is out of range 1..={n}"
+    );
     let mut pre_label = vec![0; n + 1];
     let mut post_label = vec![0; n + 1];
 
@@ -110,15 +253,13 @@ fn dfs(edge: &EdgeList, graph: &DirectedGraph, n: usize, v: usize) -> DfsTime {
         pre_label[v] = *k;
         *k += 1;
 
-        // aはvを始点とする辺のリストの先頭の辺
-        let mut a = graph.edge_first[v];
-        while a != 0 {
+        // aはvを始点とする辺
+        for a in out_edges(graph, v) {
             // aの終点
             let w = edge.head[a];
             if pre_label[w] == 0 {
                 go(w, pre_label, post_label, edge, graph, k, j);
             }
-            a = graph.edge_next[a];
         }
         post_label[v] = *j;
         *j += 1;
@@ -140,140 +281,6808 @@ fn dfs(edge: &EdgeList, graph: &DirectedGraph, n: usize, v: usize) -> DfsTime {
     }
 }
 
-fn main() {
-    // 配列のindexを1から始めるため、先頭にダミーで0を入れておく
-    let graph = EdgeList {
-        tail: vec![0, 1, 1, 6, 6, 4, 5, 3, 2, 4],
-        head: vec![0, 2, 5, 2, 5, 1, 4, 6, 3, 3],
+/// 深さ優先探索(非再帰版)
+/// dfsと同じ先行順・後行順のラベルを付けるが、再帰の代わりに明示的なスタックを使う
+/// スタックには(頂点, 次に調べる辺の番号)の組を積む
+/// 深いグラフでもコールスタックが溢れない
+/// n: 頂点数
+/// v: 探索の始点
+fn dfs_iterative(edge: &EdgeList, graph: &DirectedGraph, n: usize, v: usize) -> DfsTime {
+    dfs_with_capacity(edge, graph, n, v, 0)
+}
+
+/// 深さ優先探索(非再帰版、スタックの容量を指定する)
+/// dfs_iterativeと同じだが、スタックを最初から stack_hint 個分確保しておく
+/// グラフの深さが分かっているときに、探索中のスタックの再確保を避けられる
+/// n: 頂点数
+/// v: 探索の始点(1..=nであること)
+/// stack_hint: 予想されるスタックの最大の深さ
+fn dfs_with_capacity(
+    edge: &EdgeList,
+    graph: &DirectedGraph,
+    n: usize,
+    v: usize,
+    stack_hint: usize,
+) -> DfsTime {
+    debug_assert!(
+        (1..=n).contains(&v),
+        "start vertex {v} is out of range 1..={n}"
+    );
+    let mut time = DfsTime {
+        pre_label: vec![0; n + 1],
+        post_label: vec![0; n + 1],
     };
-    let directed_graph = dicomp_incidence_list_construct(&graph, 6, 9);
 
-    // 結果を表示
-    println!("edge_first: {:?}", directed_graph.edge_first);
-    println!("edge_next: {:?}", directed_graph.edge_next);
+    // 先行順のラベル
+    let mut k: usize = 1;
+    // 後行順のラベル
+    let mut j: usize = 1;
+
+    let mut stack = Vec::with_capacity(stack_hint);
+    dfs_iterative_from(edge, graph, v, &mut time, &mut k, &mut j, &mut stack);
+    time
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+/// 頂点vを根として非再帰で深さ優先探索を行い、timeにラベルを書き込む
+/// k, jは次に付ける先行順・後行順のラベルで、複数回の呼び出しで引き継げる
+/// stackは空の状態で渡し、呼び出しの間で確保した領域を使い回す
+fn dfs_iterative_from(
+    edge: &EdgeList,
+    graph: &DirectedGraph,
+    v: usize,
+    time: &mut DfsTime,
+    k: &mut usize,
+    j: &mut usize,
+    stack: &mut Vec<(usize, usize)>,
+) {
+    time.pre_label[v] = *k;
+    *k += 1;
+    stack.push((v, graph.edge_first[v]));
 
-    #[test]
-    fn test_dicomp_incidence_list_construct() {
-        let graph = EdgeList {
-            tail: vec![0, 1, 1, 6, 6, 4, 5, 3, 2, 4],
-            head: vec![0, 2, 5, 2, 5, 1, 4, 6, 3, 3],
-        };
-        let directed_graph = dicomp_incidence_list_construct(&graph, 6, 9);
+    while let Some(frame) = stack.last_mut() {
+        let (u, a) = *frame;
+        if a == 0 {
+            // uから出る辺を全て調べ尽くしたので後行順のラベルを付ける
+            stack.pop();
+            time.post_label[u] = *j;
+            *j += 1;
+            continue;
+        }
+        // 次に戻ってきたときはaの次の辺から調べる
+        frame.1 = graph.edge_next[a];
+        // aの終点
+        let w = edge.head[a];
+        if time.pre_label[w] == 0 {
+            time.pre_label[w] = *k;
+            *k += 1;
+            stack.push((w, graph.edge_first[w]));
+        }
+    }
+}
 
-        assert_eq!(directed_graph.edge_first, vec![0, 1, 8, 7, 5, 6, 3]);
-        assert_eq!(directed_graph.edge_next, vec![0, 2, 0, 4, 0, 9, 0, 0, 0, 0]);
-        assert_eq!(directed_graph.rev_edge_first, vec![0, 5, 1, 8, 6, 2, 7]);
-        assert_eq!(
-            directed_graph.rev_edge_next,
-            vec![0, 3, 4, 0, 0, 0, 0, 0, 9, 0]
+/// 全頂点を対象とした深さ優先探索(深さ優先探索森)
+/// 頂点1..=nを番号の小さい順に調べ、まだ訪問していない頂点から新しく探索を始める
+/// 先行順・後行順のラベルは木をまたいで通し番号になる
+/// n: 頂点数
+fn dfs_forest(edge: &EdgeList, graph: &DirectedGraph, n: usize) -> DfsTime {
+    let mut time = DfsTime {
+        pre_label: vec![0; n + 1],
+        post_label: vec![0; n + 1],
+    };
+
+    // 先行順のラベル
+    let mut k: usize = 1;
+    // 後行順のラベル
+    let mut j: usize = 1;
+
+    let mut stack = Vec::new();
+    for v in 1..=n {
+        if time.pre_label[v] == 0 {
+            dfs_iterative_from(edge, graph, v, &mut time, &mut k, &mut j, &mut stack);
+        }
+    }
+    time
+}
+
+/// 幅優先探索
+/// 頂点vから各頂点への最短路の辺数を返す
+/// dist[v] = 0 (始点)
+/// 到達できない頂点は usize::MAX とする
+/// n: 頂点数
+/// v: 探索の始点(1..=nであること)
+fn bfs(edge: &EdgeList, graph: &DirectedGraph, n: usize, v: usize) -> Vec<usize> {
+    debug_assert!(
+        (1..=n).contains(&v),
+        "start vertex {v} is out of range 1..={n}"
+    );
+    let mut dist = vec![usize::MAX; n + 1];
+    // 一度キューに入れた頂点には印を付け、多重辺や自己ループで再び入れないようにする
+    let mut visited = vec![false; n + 1];
+    let mut queue = VecDeque::new();
+
+    dist[v] = 0;
+    visited[v] = true;
+    queue.push_back(v);
+
+    while let Some(u) = queue.pop_front() {
+        for a in out_edges(graph, u) {
+            // aの終点
+            let w = edge.head[a];
+            if !visited[w] {
+                visited[w] = true;
+                dist[w] = dist[u] + 1;
+                queue.push_back(w);
+            }
+        }
+    }
+    dist
+}
+
+/// トポロジカルソート
+/// dfs_forestの後行順ラベルの大きい順(帰りがけ順の逆順)に頂点を並べたものを返す
+/// 返り値の order[i] は i 番目に処理すべき頂点(0始まりの通常の配列で、ダミーの0は含まない)
+/// どの辺 u -> w についても u が w より前に現れる
+/// 有向閉路がある場合は None を返す
+/// n: 頂点数
+fn topological_sort(edge: &EdgeList, graph: &DirectedGraph, n: usize) -> Option<Vec<usize>> {
+    let time = dfs_forest(edge, graph, n);
+
+    // 辺 u -> w で post_label[w] >= post_label[u] となるのは後退辺(wが探索中だった)のときだけ
+    for u in 1..=n {
+        for a in out_edges(graph, u) {
+            let w = edge.head[a];
+            if time.post_label[w] >= time.post_label[u] {
+                return None;
+            }
+        }
+    }
+
+    // order_by_post[j] 後行順ラベルがjの頂点
+    let mut order_by_post = vec![0; n + 1];
+    for v in 1..=n {
+        order_by_post[time.post_label[v]] = v;
+    }
+    Some(order_by_post[1..].iter().rev().copied().collect())
+}
+
+/// 強連結成分分解(Kosarajuのアルゴリズム)
+/// 1回目: dfs_forestで各頂点の後行順ラベルを求める
+/// 2回目: 後行順ラベルの大きい頂点から順に、逆向きの辺(rev_edge_first, rev_edge_next)をたどって探索する
+/// 2回目の探索1回で訪問した頂点の集合が1つの強連結成分になる
+/// component[v] 頂点vが属する強連結成分の番号(1から始まる)
+/// n: 頂点数
+fn strongly_connected_components(edge: &EdgeList, graph: &DirectedGraph, n: usize) -> Vec<usize> {
+    let time = dfs_forest(edge, graph, n);
+
+    // order_by_post[j] 後行順ラベルがjの頂点
+    let mut order_by_post = vec![0; n + 1];
+    for v in 1..=n {
+        order_by_post[time.post_label[v]] = v;
+    }
+
+    let mut component = vec![0; n + 1];
+    let mut c = 0;
+    let mut stack = Vec::new();
+    for &root in order_by_post[1..].iter().rev() {
+        if component[root] != 0 {
+            continue;
+        }
+        c += 1;
+        component[root] = c;
+        stack.push(root);
+        while let Some(u) = stack.pop() {
+            // aはuを終点とする辺
+            for a in in_edges(graph, u) {
+                // aの始点
+                let w = edge.tail[a];
+                if component[w] == 0 {
+                    component[w] = c;
+                    stack.push(w);
+                }
+            }
+        }
+    }
+    component
+}
+
+/// 辺aを接続リストの鎖から外す
+/// 鎖の中でaの直前の辺を探し、その辺がaの次の辺を指すようにする
+/// (aが先頭ならedge_first, rev_edge_firstを更新する)
+fn splice_out_edge(graph: &mut DirectedGraph, edge: &EdgeList, a: usize) {
+    fn unlink(first: &mut [usize], next: &mut [usize], v: usize, a: usize) {
+        if first[v] == a {
+            first[v] = next[a];
+        } else {
+            let mut p = first[v];
+            while p != 0 && next[p] != a {
+                p = next[p];
+            }
+            if p != 0 {
+                next[p] = next[a];
+            }
+        }
+        next[a] = 0;
+    }
+    unlink(&mut graph.edge_first, &mut graph.edge_next, edge.tail[a], a);
+    unlink(
+        &mut graph.rev_edge_first,
+        &mut graph.rev_edge_next,
+        edge.head[a],
+        a,
+    );
+}
+
+/// 辺リストと接続リストをまとめて持つグラフ
+/// 頂点数nと辺数mを自分で管理し、先頭のダミーの0も内部で入れる
+/// 接続リストは必要になったときに作り、辺を追加すると作り直す
+/// 削除した辺も番号は残し、removed[a]で印を付けて接続リストからは外す
+struct Graph {
+    n: usize,
+    edge: EdgeList,
+    removed: Vec<bool>,
+    directed_graph: OnceCell<DirectedGraph>,
+}
+
+impl Graph {
+    /// 頂点数nで辺のないグラフを作る
+    fn new(n: usize) -> Self {
+        Graph {
+            n,
+            edge: EdgeList {
+                tail: vec![0],
+                head: vec![0],
+            },
+            removed: vec![false],
+            directed_graph: OnceCell::new(),
+        }
+    }
+
+    /// 辺 tail -> head を追加し、その辺の番号を返す
+    /// tail, headは1..=nにあること(範囲外ならpanic)
+    fn add_edge(&mut self, tail: usize, head: usize) -> usize {
+        let n = self.n;
+        assert!(
+            (1..=n).contains(&tail) && (1..=n).contains(&head),
+            "edge {tail} -> {head} has an endpoint out of range 1..={n}"
         );
+        self.edge.tail.push(tail);
+        self.edge.head.push(head);
+        self.removed.push(false);
+        self.directed_graph.take();
+        self.edge_count()
     }
-    #[test]
-    fn self_loop() {
-        let g = EdgeList {
-            tail: vec![0, 2],
-            head: vec![0, 2],
-        };
-        let dg = dicomp_incidence_list_construct(&g, 3, 1);
-        assert_eq!(dg.edge_first, vec![0, 0, 1, 0]);
-        assert_eq!(dg.edge_next, vec![0, 0]);
-        assert_eq!(dg.rev_edge_first, vec![0, 0, 1, 0]);
-        assert_eq!(dg.rev_edge_next, vec![0, 0]);
+
+    /// 辺aを削除する
+    /// 接続リストが作られていれば、鎖から辺aを外すだけで済ませる
+    /// 存在しない辺や削除済みの辺を指定したときは何もせずfalseを返す
+    fn remove_edge(&mut self, a: usize) -> bool {
+        if a == 0 || a > self.edge_count() || self.removed[a] {
+            return false;
+        }
+        self.removed[a] = true;
+        if let Some(graph) = self.directed_graph.get_mut() {
+            splice_out_edge(graph, &self.edge, a);
+        }
+        true
     }
 
-    #[test]
-    fn multi_edges_same_pair() {
-        let g = EdgeList {
-            tail: vec![0, 1, 1],
-            head: vec![0, 2, 2],
-        };
-        let dg = dicomp_incidence_list_construct(&g, 2, 2);
-        assert_eq!(dg.edge_first, vec![0, 1, 0]);
-        assert_eq!(dg.edge_next, vec![0, 2, 0]);
-        assert_eq!(dg.rev_edge_first, vec![0, 0, 1]);
-        assert_eq!(dg.rev_edge_next, vec![0, 2, 0]);
+    /// 頂点数
+    fn vertex_count(&self) -> usize {
+        self.n
     }
 
-    #[test]
-    fn preserves_input_order_per_tail() {
-        // a=1..4 を (1→4),(1→3),(1→2),(1→5) とする
-        let g = EdgeList {
-            tail: vec![0, 1, 1, 1, 1],
-            head: vec![0, 4, 3, 2, 5],
-        };
-        let dg = dicomp_incidence_list_construct(&g, 5, 4);
-        assert_eq!(dg.edge_first[1], 1);
-        assert_eq!(dg.edge_next, vec![0, 2, 3, 4, 0]); // 1→2→3→4
+    /// 辺数(削除した辺も含む。辺の番号の最大値)
+    fn edge_count(&self) -> usize {
+        self.edge.tail.len() - 1
     }
 
-    #[test]
-    fn dfs_test() {
-        let graph = EdgeList {
-            tail: vec![0, 1, 1, 2, 6, 4, 5, 3, 2, 3],
-            head: vec![0, 2, 5, 6, 5, 1, 4, 6, 3, 4],
-        };
-        let directed_graph = dicomp_incidence_list_construct(&graph, 6, 9);
-        let result = dfs(&graph, &directed_graph, 6, 1);
-        assert_eq!(result.pre_label, vec![0, 1, 2, 6, 5, 4, 3]);
-        assert_eq!(result.post_label, vec![0, 6, 5, 4, 1, 2, 3]);
+    fn edge_list(&self) -> &EdgeList {
+        &self.edge
     }
-    #[test]
-    fn dfs_linear() {
-        let graph = EdgeList {
-            tail: vec![0, 1, 2],
-            head: vec![0, 2, 3],
-        };
-        let directed_graph = dicomp_incidence_list_construct(&graph, 3, 2);
-        let result = dfs(&graph, &directed_graph, 3, 1);
 
-        // 訪問順は 1,2,3
-        assert_eq!(result.pre_label, vec![0, 1, 2, 3]);
-        // 帰りがけ順は逆順
-        assert_eq!(result.post_label, vec![0, 3, 2, 1]);
+    /// 接続リスト(まだ作っていなければここで作る)
+    fn directed_graph(&self) -> &DirectedGraph {
+        self.directed_graph.get_or_init(|| {
+            let mut graph = dicomp_incidence_list_construct(&self.edge, self.n, self.edge_count());
+            for a in (1..=self.edge_count()).filter(|&a| self.removed[a]) {
+                splice_out_edge(&mut graph, &self.edge, a);
+            }
+            graph
+        })
     }
-    /// 分岐を持つ木構造: 1 -> 2, 1 -> 3
-    #[test]
-    fn dfs_branching() {
-        let graph = EdgeList {
-            tail: vec![0, 1, 1],
-            head: vec![0, 2, 3],
-        };
-        let directed_graph = dicomp_incidence_list_construct(&graph, 3, 2);
-        let result = dfs(&graph, &directed_graph, 3, 1);
 
-        // 先に 2, 次に 3 を探索すると仮定
-        assert_eq!(result.pre_label[1], 1);
-        assert!(result.pre_label[2] < result.pre_label[3]);
-        assert!(result.post_label[1] > result.post_label[2]);
-        assert!(result.post_label[1] > result.post_label[3]);
+    /// 頂点vを始点とする深さ優先探索
+    fn dfs(&self, v: usize) -> DfsTime {
+        dfs_iterative(&self.edge, self.directed_graph(), self.n, v)
     }
 
-    #[test]
-    fn dfs_cycle() {
-        let graph = EdgeList {
-            tail: vec![0, 1, 2, 3],
-            head: vec![0, 2, 3, 1],
-        };
-        let directed_graph = dicomp_incidence_list_construct(&graph, 3, 3);
-        let result = dfs(&graph, &directed_graph, 3, 1);
+    /// 頂点vを始点とする幅優先探索
+    fn bfs(&self, v: usize) -> Vec<usize> {
+        bfs(&self.edge, self.directed_graph(), self.n, v)
+    }
+}
 
-        // すべて訪問できている（pre_labelが全て非ゼロ）
-        assert!(result.pre_label[1..=3].iter().all(|&x| x > 0));
+/// 各頂点の出次数と入次数
+/// out_degree[v] 頂点vを始点とする辺の数(edge_first, edge_nextの鎖の長さ)
+/// in_degree[v] 頂点vを終点とする辺の数(rev_edge_first, rev_edge_nextの鎖の長さ)
+/// 自己ループは同じ頂点の出次数と入次数の両方に数える
+/// n: 頂点数
+fn degrees(_edge: &EdgeList, graph: &DirectedGraph, n: usize) -> (Vec<usize>, Vec<usize>) {
+    let mut out_degree = vec![0; n + 1];
+    let mut in_degree = vec![0; n + 1];
+    for v in 1..=n {
+        out_degree[v] = out_edges(graph, v).count();
+        in_degree[v] = in_edges(graph, v).count();
     }
-    #[test]
-    fn dfs_disconnected() {
-        let graph = EdgeList {
-            tail: vec![0, 1, 3],
-            head: vec![0, 2, 4],
-        };
-        let directed_graph = dicomp_incidence_list_construct(&graph, 4, 2);
-        let result = dfs(&graph, &directed_graph, 4, 1);
-        assert_eq!(result.pre_label, vec![0, 1, 2, 0, 0]);
-        assert_eq!(result.post_label, vec![0, 2, 1, 0, 0]);
+    (out_degree, in_degree)
+}
+
+/// トポロジカルソート(Kahnのアルゴリズム)
+/// 入次数0の頂点を最小ヒープに入れ、取り出した頂点から出る辺を取り除くことを繰り返す
+/// 入次数0の頂点が複数あるときは番号の最小のものを先に取り出すので、結果は入力に対して一意に決まる
+/// (順序は辞書順で最小のトポロジカル順序になる)
+/// 成功したときは取り出した順に並べた頂点を返す
+/// 閉路が残ったときは一度も取り出されなかった頂点を番号順に並べてErrで返す
+/// n: 頂点数
+fn topological_sort_kahn(
+    edge: &EdgeList,
+    graph: &DirectedGraph,
+    n: usize,
+) -> Result<Vec<usize>, Vec<usize>> {
+    let (_, mut in_degree) = degrees(edge, graph, n);
+    let mut heap: BinaryHeap<Reverse<usize>> = (1..=n)
+        .filter(|&v| in_degree[v] == 0)
+        .map(Reverse)
+        .collect();
+    let mut order = Vec::with_capacity(n);
+
+    while let Some(Reverse(u)) = heap.pop() {
+        order.push(u);
+        for a in out_edges(graph, u) {
+            let w = edge.head[a];
+            in_degree[w] -= 1;
+            if in_degree[w] == 0 {
+                heap.push(Reverse(w));
+            }
+        }
+    }
+
+    if order.len() == n {
+        Ok(order)
+    } else {
+        Err((1..=n).filter(|&v| in_degree[v] > 0).collect())
+    }
+}
+
+/// 深さ優先探索での頂点の状態
+/// White: 未訪問, Gray: 探索中(スタックに載っている), Black: 探索完了
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Color {
+    White,
+    Gray,
+    Black,
+}
+
+/// 有向閉路があるかどうか
+/// 全頂点から三色の深さ優先探索を行い、探索中(Gray)の頂点への辺(後退辺)が見つかれば閉路がある
+/// 自己ループも閉路とみなす
+/// n: 頂点数
+fn has_cycle(edge: &EdgeList, graph: &DirectedGraph, n: usize) -> bool {
+    let mut color = vec![Color::White; n + 1];
+    let mut stack: Vec<(usize, usize)> = Vec::new();
+
+    for root in 1..=n {
+        if color[root] != Color::White {
+            continue;
+        }
+        color[root] = Color::Gray;
+        stack.push((root, graph.edge_first[root]));
+        while let Some(frame) = stack.last_mut() {
+            let (u, a) = *frame;
+            if a == 0 {
+                stack.pop();
+                color[u] = Color::Black;
+                continue;
+            }
+            frame.1 = graph.edge_next[a];
+            let w = edge.head[a];
+            match color[w] {
+                Color::White => {
+                    color[w] = Color::Gray;
+                    stack.push((w, graph.edge_first[w]));
+                }
+                Color::Gray => return true,
+                Color::Black => {}
+            }
+        }
+    }
+    false
+}
+
+/// 転置グラフ(全ての辺の向きを逆にしたグラフ)の辺リスト
+/// 辺aの始点と終点を入れ替えるだけなので、辺の番号はそのまま保たれる
+/// m: 辺数
+fn transpose(edge: &EdgeList, m: usize) -> EdgeList {
+    EdgeList {
+        tail: edge.head[..=m].to_vec(),
+        head: edge.tail[..=m].to_vec(),
+    }
+}
+
+/// ダイクストラ法による単一始点最短路
+/// weight[a] 辺aの重み(先頭にダミーの0を入れ、辺の番号と揃える)
+/// 重みは全て非負であること(負の重みがあるときの結果は保証しない)
+/// dist[v] sourceから頂点vへの最短路の長さ(到達できない頂点は i64::MAX)
+/// n: 頂点数
+/// source: 始点
+fn dijkstra(
+    edge: &EdgeList,
+    graph: &DirectedGraph,
+    weight: &[i64],
+    n: usize,
+    source: usize,
+) -> Vec<i64> {
+    let mut dist = vec![i64::MAX; n + 1];
+    // (暫定距離, 頂点) の最小ヒープ
+    let mut heap = BinaryHeap::new();
+
+    dist[source] = 0;
+    heap.push(Reverse((0, source)));
+
+    while let Some(Reverse((d, u))) = heap.pop() {
+        // 既により短い距離で確定している場合は古い要素なので読み飛ばす
+        if d > dist[u] {
+            continue;
+        }
+        for a in out_edges(graph, u) {
+            let w = edge.head[a];
+            let candidate = d + weight[a];
+            if candidate < dist[w] {
+                dist[w] = candidate;
+                heap.push(Reverse((candidate, w)));
+            }
+        }
+    }
+    dist
+}
+
+/// 始点から到達できる負閉路が見つかったことを表すエラー
+/// vertexには閉路上の頂点を1つ入れる(特定できなかったときはNone)
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct NegativeCycle {
+    vertex: Option<usize>,
+}
+
+impl fmt::Display for NegativeCycle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.vertex {
+            Some(v) => write!(f, "negative cycle through vertex {v}"),
+            None => write!(f, "negative cycle"),
+        }
+    }
+}
+
+impl std::error::Error for NegativeCycle {}
+
+/// ベルマン・フォード法による単一始点最短路
+/// 全ての辺の緩和をn-1回繰り返したあと、もう1回緩和を試みて更新が起きれば負閉路がある
+/// 負の重みを持つ辺があってもよい
+/// weight[a] 辺aの重み(先頭にダミーの0を入れ、辺の番号と揃える)
+/// dist[v] sourceから頂点vへの最短路の長さ(到達できない頂点は i64::MAX)
+/// n: 頂点数
+/// source: 始点
+fn bellman_ford(
+    edge: &EdgeList,
+    graph: &DirectedGraph,
+    weight: &[i64],
+    n: usize,
+    source: usize,
+) -> Result<Vec<i64>, NegativeCycle> {
+    let mut dist = vec![i64::MAX; n + 1];
+    // pred[v] vの暫定最短路の最後の辺の始点
+    let mut pred = vec![0; n + 1];
+    dist[source] = 0;
+
+    // 1回の緩和で更新が起きた頂点(n回目に更新されれば負閉路の影響を受けている)
+    let mut updated = 0;
+    for _ in 0..n {
+        updated = 0;
+        for u in 1..=n {
+            if dist[u] == i64::MAX {
+                continue;
+            }
+            for a in out_edges(graph, u) {
+                let w = edge.head[a];
+                let candidate = dist[u] + weight[a];
+                if candidate < dist[w] {
+                    dist[w] = candidate;
+                    pred[w] = u;
+                    updated = w;
+                }
+            }
+        }
+        if updated == 0 {
+            return Ok(dist);
+        }
+    }
+
+    // n回目に更新された頂点からpredをn回たどれば必ず閉路上に着く
+    let mut v = updated;
+    for _ in 0..n {
+        v = pred[v];
+    }
+    Err(NegativeCycle {
+        vertex: (v != 0).then_some(v),
+    })
+}
+
+/// Graphvizで読めるDOT形式の文字列にする
+/// 辺は番号順に1行ずつ `u -> v;` と書くので、多重辺はその本数だけ、自己ループは `v -> v;` になる
+/// どの辺の端点にもならない頂点は `v;` と宣言して図に残るようにする
+/// n: 頂点数
+/// m: 辺数
+fn to_dot(edge: &EdgeList, n: usize, m: usize) -> String {
+    let mut incident = vec![false; n + 1];
+    for a in 1..=m {
+        incident[edge.tail[a]] = true;
+        incident[edge.head[a]] = true;
+    }
+
+    let mut dot = String::from("digraph {\n");
+    for v in (1..=n).filter(|&v| !incident[v]) {
+        writeln!(dot, "    {v};").unwrap();
+    }
+    for a in 1..=m {
+        writeln!(dot, "    {} -> {};", edge.tail[a], edge.head[a]).unwrap();
+    }
+    dot.push_str("}\n");
+    dot
+}
+
+/// テキストからの読み込みに失敗した理由
+/// lineは1から数えた行番号
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum ParseError {
+    /// 数として読めない語があった
+    InvalidToken { line: usize, token: String },
+    /// 1行の語の数がおかしい
+    WrongTokenCount { line: usize },
+    /// 頂点の番号が1..=nの範囲にない
+    VertexOutOfRange { line: usize, vertex: usize },
+    /// DIMACS形式で`p`行より前に辺があった、または`p`行がなかった
+    MissingProblemLine { line: usize },
+    /// DIMACS形式で`c`, `p`, `e`のどれでもない行、または2つめの`p`行があった
+    UnexpectedLine { line: usize },
+    /// DIMACS形式で`p`行に書かれた辺数と`e`行の数が一致しない
+    EdgeCountMismatch { expected: usize, found: usize },
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::InvalidToken { line, token } => {
+                write!(f, "line {line}: invalid token {token:?}")
+            }
+            ParseError::WrongTokenCount { line } => {
+                write!(f, "line {line}: wrong number of tokens")
+            }
+            ParseError::VertexOutOfRange { line, vertex } => {
+                write!(f, "line {line}: vertex {vertex} out of range")
+            }
+            ParseError::MissingProblemLine { line } => {
+                write!(f, "line {line}: missing problem line")
+            }
+            ParseError::UnexpectedLine { line } => {
+                write!(f, "line {line}: unexpected line")
+            }
+            ParseError::EdgeCountMismatch { expected, found } => {
+                write!(f, "expected {expected} edges, found {found}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// 1行に1本ずつ `tail head` と書いた辺の一覧を読み込む
+/// 頂点の番号は1から始まり、`#`から行末まではコメントとして読み飛ばす
+/// 空行も読み飛ばす
+/// 返り値は先頭にダミーの0を入れた辺リストと辺数m
+/// n: 頂点数
+fn from_edge_text(input: &str, n: usize) -> Result<(EdgeList, usize), ParseError> {
+    let mut edge = EdgeList {
+        tail: vec![0],
+        head: vec![0],
+    };
+    let mut m = 0;
+
+    for (i, raw) in input.lines().enumerate() {
+        let line = i + 1;
+        let content = raw.split('#').next().unwrap_or("");
+        let tokens: Vec<&str> = content.split_whitespace().collect();
+        if tokens.is_empty() {
+            continue;
+        }
+        if tokens.len() != 2 {
+            return Err(ParseError::WrongTokenCount { line });
+        }
+        let mut ends = [0; 2];
+        for (end, token) in ends.iter_mut().zip(&tokens) {
+            let vertex: usize = token.parse().map_err(|_| ParseError::InvalidToken {
+                line,
+                token: token.to_string(),
+            })?;
+            if vertex == 0 || vertex > n {
+                return Err(ParseError::VertexOutOfRange { line, vertex });
+            }
+            *end = vertex;
+        }
+        edge.tail.push(ends[0]);
+        edge.head.push(ends[1]);
+        m += 1;
+    }
+    Ok((edge, m))
+}
+
+/// 辺の向きを無視したときに頂点uに接続する辺を (辺の番号, 反対側の頂点) の組で返す
+/// uから出る辺を先に、uに入る辺を後に返すので、自己ループは2回現れる
+fn undirected_edges<'a>(
+    edge: &'a EdgeList,
+    graph: &'a DirectedGraph,
+    u: usize,
+) -> impl Iterator<Item = (usize, usize)> + 'a {
+    let forward = out_edges(graph, u).map(|a| (a, edge.head[a]));
+    let backward = in_edges(graph, u).map(|a| (a, edge.tail[a]));
+    forward.chain(backward)
+}
+
+/// 辺の向きを無視したときの連結成分(弱連結成分)
+/// 各頂点から出る辺(edge_first, edge_next)と入る辺(rev_edge_first, rev_edge_next)の両方をたどる
+/// component[v] 頂点vが属する連結成分の番号
+/// 番号は頂点1..=nを順に見て、初めて現れた成分から1, 2, ... と付ける
+/// n: 頂点数
+fn connected_components(edge: &EdgeList, graph: &DirectedGraph, n: usize) -> Vec<usize> {
+    let mut component = vec![0; n + 1];
+    let mut c = 0;
+    let mut stack = Vec::new();
+
+    for root in 1..=n {
+        if component[root] != 0 {
+            continue;
+        }
+        c += 1;
+        component[root] = c;
+        stack.push(root);
+        while let Some(u) = stack.pop() {
+            for (_, w) in undirected_edges(edge, graph, u) {
+                if component[w] == 0 {
+                    component[w] = c;
+                    stack.push(w);
+                }
+            }
+        }
+    }
+    component
+}
+
+/// 2部グラフかどうか(辺の向きを無視して判定する)
+/// 連結成分ごとに幅優先探索で隣り合う頂点に1と2を交互に塗る
+/// 2部グラフならSome(color)を返す。color[v]は頂点vの色で1か2(color[0]はダミーの0)
+/// 奇閉路があればNoneを返す。自己ループがあれば2部グラフではない
+/// n: 頂点数
+fn is_bipartite(edge: &EdgeList, graph: &DirectedGraph, n: usize) -> Option<Vec<u8>> {
+    let mut color = vec![0u8; n + 1];
+    let mut queue = VecDeque::new();
+
+    for root in 1..=n {
+        if color[root] != 0 {
+            continue;
+        }
+        color[root] = 1;
+        queue.push_back(root);
+        while let Some(u) = queue.pop_front() {
+            for (_, w) in undirected_edges(edge, graph, u) {
+                if color[w] == 0 {
+                    color[w] = 3 - color[u];
+                    queue.push_back(w);
+                } else if color[w] == color[u] {
+                    return None;
+                }
+            }
+        }
+    }
+    Some(color)
+}
+
+/// 辺の向きを無視した深さ優先探索森で求めるlowlink
+/// pre_label[v] 頂点vを最初に訪問した順番
+/// low[v] vの子孫から木の辺を使わずに(後退辺を高々1本使って)たどり着ける頂点のpre_labelの最小値
+/// parent_edge[v] vを発見したときに使った辺の番号(根は0)
+struct LowLink {
+    pre_label: Vec<usize>,
+    low: Vec<usize>,
+    parent_edge: Vec<usize>,
+}
+
+/// 頂点1..=nを順に根として、向きを無視した深さ優先探索を非再帰で行いlowlinkを求める
+/// 親へ戻る辺は辺の番号で区別して1本だけ除くので、多重辺は後退辺として扱われる
+/// n: 頂点数
+fn lowlink(edge: &EdgeList, graph: &DirectedGraph, n: usize) -> LowLink {
+    let mut pre_label = vec![0; n + 1];
+    let mut low = vec![0; n + 1];
+    let mut parent_edge = vec![0; n + 1];
+    let mut k = 1;
+
+    for root in 1..=n {
+        if pre_label[root] != 0 {
+            continue;
+        }
+        pre_label[root] = k;
+        low[root] = k;
+        k += 1;
+        let mut stack = vec![(root, undirected_edges(edge, graph, root))];
+        while let Some((u, edges)) = stack.last_mut() {
+            let u = *u;
+            match edges.next() {
+                Some((a, _)) if a == parent_edge[u] => {}
+                Some((a, w)) => {
+                    if pre_label[w] == 0 {
+                        pre_label[w] = k;
+                        low[w] = k;
+                        k += 1;
+                        parent_edge[w] = a;
+                        stack.push((w, undirected_edges(edge, graph, w)));
+                    } else {
+                        low[u] = low[u].min(pre_label[w]);
+                    }
+                }
+                None => {
+                    stack.pop();
+                    if let Some(&(p, _)) = stack.last() {
+                        low[p] = low[p].min(low[u]);
+                    }
+                }
+            }
+        }
+    }
+    LowLink {
+        pre_label,
+        low,
+        parent_edge,
+    }
+}
+
+/// 関節点(取り除くと連結成分が増える頂点)を番号順に返す
+/// 辺の向きは無視する
+/// 根でない頂点uは、ある子cについて low[c] >= pre_label[u] となるとき関節点
+/// 深さ優先探索木の根は子が2つ以上あるとき関節点
+/// n: 頂点数
+fn articulation_points(edge: &EdgeList, graph: &DirectedGraph, n: usize) -> Vec<usize> {
+    let link = lowlink(edge, graph, n);
+    let mut children = vec![0; n + 1];
+    let mut is_cut = vec![false; n + 1];
+
+    for c in 1..=n {
+        let a = link.parent_edge[c];
+        if a == 0 {
+            continue;
+        }
+        // 木の辺aのcでない側の端点が親
+        let p = if edge.head[a] == c {
+            edge.tail[a]
+        } else {
+            edge.head[a]
+        };
+        children[p] += 1;
+        if link.parent_edge[p] != 0 && link.low[c] >= link.pre_label[p] {
+            is_cut[p] = true;
+        }
+    }
+    (1..=n)
+        .filter(|&v| is_cut[v] || (link.parent_edge[v] == 0 && children[v] >= 2))
+        .collect()
+}
+
+/// 橋(取り除くと連結成分が増える辺)の番号を昇順に返す
+/// 辺の向きは無視する
+/// 木の辺 p - c は low[c] > pre_label[p] のとき橋
+/// 同じ2頂点を結ぶ多重辺は互いに迂回路になるので橋にはならない
+/// n: 頂点数
+fn bridges(edge: &EdgeList, graph: &DirectedGraph, n: usize) -> Vec<usize> {
+    let link = lowlink(edge, graph, n);
+    let mut result: Vec<usize> = (1..=n)
+        .filter_map(|c| {
+            let a = link.parent_edge[c];
+            if a == 0 {
+                return None;
+            }
+            let p = if edge.head[a] == c {
+                edge.tail[a]
+            } else {
+                edge.head[a]
+            };
+            (link.low[c] > link.pre_label[p]).then_some(a)
+        })
+        .collect();
+    result.sort_unstable();
+    result
+}
+
+/// 深さ優先探索での辺の種類
+/// Tree: 新しい頂点を発見した辺
+/// Back: 祖先へ戻る辺(自己ループを含む)
+/// Forward: 木の辺以外で子孫へ進む辺
+/// Cross: それ以外(探索済みの別の部分木へ向かう辺)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum EdgeKind {
+    Tree,
+    Back,
+    Forward,
+    Cross,
+}
+
+/// 深さ優先探索森(dfs_forestと同じ順番)で各辺を分類する
+/// kind[a] 辺aの種類。kind[0]はダミーでTreeにしておく
+/// 辺 u -> w について、木の辺以外は端点のラベルで決まる
+///   pre_label[w] <= pre_label[u] かつ post_label[w] >= post_label[u] なら Back
+///   pre_label[w] > pre_label[u] かつ post_label[w] < post_label[u] なら Forward
+///   それ以外は Cross
+/// n: 頂点数
+fn classify_edges(edge: &EdgeList, graph: &DirectedGraph, n: usize) -> Vec<EdgeKind> {
+    let m = edge.tail.len() - 1;
+    let mut pre_label = vec![0; n + 1];
+    let mut post_label = vec![0; n + 1];
+    let mut is_tree = vec![false; m + 1];
+    let mut k = 1;
+    let mut j = 1;
+
+    for root in 1..=n {
+        if pre_label[root] != 0 {
+            continue;
+        }
+        pre_label[root] = k;
+        k += 1;
+        let mut stack = vec![(root, graph.edge_first[root])];
+        while let Some(frame) = stack.last_mut() {
+            let (u, a) = *frame;
+            if a == 0 {
+                stack.pop();
+                post_label[u] = j;
+                j += 1;
+                continue;
+            }
+            frame.1 = graph.edge_next[a];
+            let w = edge.head[a];
+            if pre_label[w] == 0 {
+                is_tree[a] = true;
+                pre_label[w] = k;
+                k += 1;
+                stack.push((w, graph.edge_first[w]));
+            }
+        }
+    }
+
+    let mut kind = vec![EdgeKind::Tree; m + 1];
+    for a in 1..=m {
+        let (u, w) = (edge.tail[a], edge.head[a]);
+        kind[a] = if is_tree[a] {
+            EdgeKind::Tree
+        } else if pre_label[w] <= pre_label[u] && post_label[w] >= post_label[u] {
+            EdgeKind::Back
+        } else if pre_label[w] > pre_label[u] && post_label[w] < post_label[u] {
+            EdgeKind::Forward
+        } else {
+            EdgeKind::Cross
+        };
+    }
+    kind
+}
+
+/// 頂点を0から数える利用者向けのグラフ
+/// 内部では頂点の番号に1を足してGraph(1から数える版)に渡し、結果から先頭のダミーを取り除く
+struct Graph0 {
+    graph: Graph,
+}
+
+impl Graph0 {
+    /// 頂点0..nと辺の一覧から作る
+    /// edges[i] = (tail, head) が i 番目の辺
+    fn from_edges(n: usize, edges: &[(usize, usize)]) -> Self {
+        let mut graph = Graph::new(n);
+        for &(tail, head) in edges {
+            graph.add_edge(tail + 1, head + 1);
+        }
+        Graph0 { graph }
+    }
+
+    /// 頂点sourceを始点とする深さ優先探索
+    /// 返すラベルの配列は長さnで、頂点vのラベルは pre_label[v], post_label[v]
+    /// ラベルの値自体は1から数えた順番で、訪問しなかった頂点は0
+    fn dfs(&self, source: usize) -> DfsTime {
+        let time = self.graph.dfs(source + 1);
+        DfsTime {
+            pre_label: time.pre_label[1..].to_vec(),
+            post_label: time.post_label[1..].to_vec(),
+        }
+    }
+
+    /// 頂点sourceを始点とする幅優先探索
+    /// 返す配列は長さnで、到達できない頂点は usize::MAX
+    fn bfs(&self, source: usize) -> Vec<usize> {
+        self.graph.bfs(source + 1)[1..].to_vec()
+    }
+}
+
+/// 推移的閉包(到達可能性の行列)
+/// reach[u][v] 頂点uから頂点vへ長さ1以上の有向路があればtrue
+/// reach[v][v]はvが閉路上にある(自己ループを含む)ときだけtrueになる
+/// 反射推移的閉包が欲しいときは対角成分をtrueにすればよい
+/// 各頂点から深さ優先探索を行うのでO(n(n+m))
+/// n: 頂点数
+fn reachability(edge: &EdgeList, graph: &DirectedGraph, n: usize) -> Vec<Vec<bool>> {
+    let mut reach = vec![vec![false; n + 1]; n + 1];
+    let mut stack = Vec::new();
+
+    for (u, row) in reach.iter_mut().enumerate().skip(1) {
+        // u自身は最初は訪問済みにせず、uから出る辺の先から探索を始める
+        stack.push(u);
+        while let Some(x) = stack.pop() {
+            for a in out_edges(graph, x) {
+                let w = edge.head[a];
+                if !row[w] {
+                    row[w] = true;
+                    stack.push(w);
+                }
+            }
+        }
+    }
+    reach
+}
+
+/// 頂点fromから頂点toへ長さ1以上の有向路があるかどうか
+/// fromから深さ優先探索を行い、toを見つけた時点で打ち切る
+/// 使う領域は訪問済みの印の n+1 個だけ
+/// from == to のときも自明にtrueとはせず、自己ループかfromに戻る閉路があるときだけtrueを返す
+/// (reachabilityの reach[v][v] と同じ意味。長さ0の路も認めたいときは呼び出し側で from == to を調べる)
+/// n: 頂点数
+fn can_reach(edge: &EdgeList, graph: &DirectedGraph, n: usize, from: usize, to: usize) -> bool {
+    let mut visited = vec![false; n + 1];
+    let mut stack = vec![from];
+    while let Some(u) = stack.pop() {
+        for a in out_edges(graph, u) {
+            let w = edge.head[a];
+            if w == to {
+                return true;
+            }
+            if !visited[w] {
+                visited[w] = true;
+                stack.push(w);
+            }
+        }
+    }
+    false
+}
+
+/// Tarjanのアルゴリズムの本体
+/// 1回の深さ優先探索で強連結成分を求め、成分が確定する(スタックから取り除かれる)たびに
+/// その成分の頂点の一覧を引数にしてon_componentを呼ぶ
+/// 逆向きの辺(rev_edge_first, rev_edge_next)は使わない
+/// n: 頂点数
+fn tarjan<F: FnMut(&[usize])>(
+    edge: &EdgeList,
+    graph: &DirectedGraph,
+    n: usize,
+    mut on_component: F,
+) {
+    // index[v] 頂点vを訪問した順番(0は未訪問)
+    let mut index = vec![0; n + 1];
+    // low[v] vの子孫から到達できる、まだ成分が確定していない頂点のindexの最小値
+    let mut low = vec![0; n + 1];
+    let mut on_stack = vec![false; n + 1];
+    let mut stack: Vec<usize> = Vec::new();
+    let mut k = 1;
+
+    for root in 1..=n {
+        if index[root] != 0 {
+            continue;
+        }
+        index[root] = k;
+        low[root] = k;
+        k += 1;
+        stack.push(root);
+        on_stack[root] = true;
+        let mut frames = vec![(root, graph.edge_first[root])];
+        while let Some(frame) = frames.last_mut() {
+            let (u, a) = *frame;
+            if a != 0 {
+                frame.1 = graph.edge_next[a];
+                let w = edge.head[a];
+                if index[w] == 0 {
+                    index[w] = k;
+                    low[w] = k;
+                    k += 1;
+                    stack.push(w);
+                    on_stack[w] = true;
+                    frames.push((w, graph.edge_first[w]));
+                } else if on_stack[w] {
+                    low[u] = low[u].min(index[w]);
+                }
+                continue;
+            }
+
+            frames.pop();
+            if let Some(&(p, _)) = frames.last() {
+                low[p] = low[p].min(low[u]);
+            }
+            if low[u] == index[u] {
+                // uが成分の根なので、スタックのuより上の頂点がuと同じ成分になる
+                let start = stack.iter().rposition(|&x| x == u).unwrap();
+                for &x in &stack[start..] {
+                    on_stack[x] = false;
+                }
+                on_component(&stack[start..]);
+                stack.truncate(start);
+            }
+        }
+    }
+}
+
+/// 強連結成分分解(Tarjanのアルゴリズム)
+/// component[v] 頂点vが属する強連結成分の番号
+/// 番号は成分が確定した順に1から付ける(逆位相順になる)
+/// n: 頂点数
+fn scc_tarjan(edge: &EdgeList, graph: &DirectedGraph, n: usize) -> Vec<usize> {
+    let mut component = vec![0; n + 1];
+    let mut c = 0;
+    tarjan(edge, graph, n, |vertices| {
+        c += 1;
+        for &v in vertices {
+            component[v] = c;
+        }
+    });
+    component
+}
+
+/// 縮約グラフ(強連結成分をそれぞれ1つの頂点に縮めた非巡回グラフ)
+/// 縮約後の頂点の番号はstrongly_connected_componentsの成分番号
+/// 成分の間の辺は向きごとに1本だけ残し(最初に現れた順)、成分の中の辺は取り除く
+/// 返り値は縮約後の辺リストと頂点数
+/// n: 頂点数
+fn condensation(edge: &EdgeList, graph: &DirectedGraph, n: usize) -> (EdgeList, usize) {
+    let component = strongly_connected_components(edge, graph, n);
+    let count = component.iter().copied().max().unwrap_or(0);
+    let m = edge.tail.len() - 1;
+
+    let mut result = EdgeList {
+        tail: vec![0],
+        head: vec![0],
+    };
+    let mut seen = HashSet::new();
+    for a in 1..=m {
+        let (cu, cw) = (component[edge.tail[a]], component[edge.head[a]]);
+        if cu != cw && seen.insert((cu, cw)) {
+            result.tail.push(cu);
+            result.head.push(cw);
+        }
+    }
+    (result, count)
+}
+
+/// 非巡回グラフで頂点fromから頂点toへの有向路の数を数える
+/// paths[v] = (vからtoへの路の数) をメモしながら深さ優先探索で求める
+///   paths[to] = 1, paths[v] = vから出る辺 v -> w についての paths[w] の和
+/// fromから到達できる範囲に閉路があるとき、または数が u64 に収まらないときは None を返す
+/// n: 頂点数
+fn count_paths_checked(
+    edge: &EdgeList,
+    graph: &DirectedGraph,
+    n: usize,
+    from: usize,
+    to: usize,
+) -> Option<u64> {
+    let mut color = vec![Color::White; n + 1];
+    let mut paths = vec![0u64; n + 1];
+
+    color[from] = Color::Gray;
+    let mut stack = vec![(from, graph.edge_first[from])];
+    while let Some(frame) = stack.last_mut() {
+        let (u, a) = *frame;
+        if a == 0 {
+            stack.pop();
+            color[u] = Color::Black;
+            if u == to {
+                paths[u] = 1;
+            }
+            if let Some(&(p, _)) = stack.last() {
+                paths[p] = paths[p].checked_add(paths[u])?;
+            }
+            continue;
+        }
+        frame.1 = graph.edge_next[a];
+        let w = edge.head[a];
+        match color[w] {
+            Color::White => {
+                color[w] = Color::Gray;
+                stack.push((w, graph.edge_first[w]));
+            }
+            Color::Gray => return None,
+            Color::Black => paths[u] = paths[u].checked_add(paths[w])?,
+        }
+    }
+    Some(paths[from])
+}
+
+/// 非巡回グラフで頂点fromから頂点toへの有向路の数を数える
+/// グラフが非巡回であること(fromから閉路に到達できるとき、または数が u64 に収まらないときはpanicする)
+/// panicさせたくないときはcount_paths_checkedを使う
+/// n: 頂点数
+fn count_paths(edge: &EdgeList, graph: &DirectedGraph, n: usize, from: usize, to: usize) -> u64 {
+    count_paths_checked(edge, graph, n, from, to)
+        .expect("count_paths requires a DAG and a path count that fits in u64")
+}
+
+/// 深さ優先探索の帰りがけ順に頂点を1つずつ返すイテレータ
+/// post_labelの配列を作らずに、探索が完了した頂点から順に取り出せる
+struct PostOrder<'a> {
+    edge: &'a EdgeList,
+    graph: &'a DirectedGraph,
+    visited: Vec<bool>,
+    // (頂点, 次に調べる辺の番号)
+    stack: Vec<(usize, usize)>,
+}
+
+impl Iterator for PostOrder<'_> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        while let Some(frame) = self.stack.last_mut() {
+            let (u, a) = *frame;
+            if a == 0 {
+                self.stack.pop();
+                return Some(u);
+            }
+            frame.1 = self.graph.edge_next[a];
+            let w = self.edge.head[a];
+            if !self.visited[w] {
+                self.visited[w] = true;
+                self.stack.push((w, self.graph.edge_first[w]));
+            }
+        }
+        None
+    }
+}
+
+/// 頂点sourceからの深さ優先探索で、探索が完了した順(post_labelの小さい順)に頂点を返す
+/// n: 頂点数
+/// source: 探索の始点
+fn post_order<'a>(
+    edge: &'a EdgeList,
+    graph: &'a DirectedGraph,
+    n: usize,
+    source: usize,
+) -> PostOrder<'a> {
+    let mut visited = vec![false; n + 1];
+    visited[source] = true;
+    PostOrder {
+        edge,
+        graph,
+        visited,
+        stack: vec![(source, graph.edge_first[source])],
+    }
+}
+
+/// 有向閉路を1つ見つけて、その頂点を閉路をたどる順に返す
+/// has_cycleと同じ三色の深さ優先探索で後退辺 u -> w を見つけたら、
+/// スタック上のwからuまでの頂点を並べたものが閉路になる(最後のuからwへ戻る)
+/// 自己ループ v -> v は [v] を返す
+/// 閉路がなければNone
+/// n: 頂点数
+fn find_cycle(edge: &EdgeList, graph: &DirectedGraph, n: usize) -> Option<Vec<usize>> {
+    let mut color = vec![Color::White; n + 1];
+    let mut stack: Vec<(usize, usize)> = Vec::new();
+
+    for root in 1..=n {
+        if color[root] != Color::White {
+            continue;
+        }
+        color[root] = Color::Gray;
+        stack.push((root, graph.edge_first[root]));
+        while let Some(frame) = stack.last_mut() {
+            let (u, a) = *frame;
+            if a == 0 {
+                stack.pop();
+                color[u] = Color::Black;
+                continue;
+            }
+            frame.1 = graph.edge_next[a];
+            let w = edge.head[a];
+            match color[w] {
+                Color::White => {
+                    color[w] = Color::Gray;
+                    stack.push((w, graph.edge_first[w]));
+                }
+                Color::Gray => {
+                    // スタックに積まれている頂点は根からuまでの路になっている
+                    let start = stack.iter().position(|&(x, _)| x == w).unwrap();
+                    return Some(stack[start..].iter().map(|&(x, _)| x).collect());
+                }
+                Color::Black => {}
+            }
+        }
+    }
+    None
+}
+
+/// n×nのbool行列(0始まり)から辺リストを作る
+/// matrix[i][j] が true なら頂点 i+1 から頂点 j+1 への辺を1本加える(対角成分は自己ループ)
+/// 辺の番号は行優先で (i, j) の小さい順に付ける
+/// 返り値は辺リスト, 頂点数n, 辺数m
+fn from_adjacency_matrix(matrix: &[Vec<bool>]) -> (EdgeList, usize, usize) {
+    let n = matrix.len();
+    let mut edge = EdgeList {
+        tail: vec![0],
+        head: vec![0],
+    };
+    for (i, row) in matrix.iter().enumerate() {
+        for (j, _) in row.iter().enumerate().filter(|&(_, &x)| x) {
+            edge.tail.push(i + 1);
+            edge.head.push(j + 1);
+        }
+    }
+    let m = edge.tail.len() - 1;
+    (edge, n, m)
+}
+
+/// 辺リストからn×nの隣接行列(0始まり)を作る
+/// matrix[i][j] 頂点 i+1 から頂点 j+1 への辺の本数
+/// 多重辺は2以上の値になり、自己ループは対角成分に現れる
+/// n: 頂点数
+/// m: 辺数
+fn to_adjacency_matrix(edge: &EdgeList, n: usize, m: usize) -> Vec<Vec<u32>> {
+    let mut matrix = vec![vec![0; n]; n];
+    for a in 1..=m {
+        matrix[edge.tail[a] - 1][edge.head[a] - 1] += 1;
+    }
+    matrix
+}
+
+/// 素集合データ構造(Union-Find)
+/// parent[v] 頂点vの親(根ならv自身)
+/// rank[v] vを根とする木の高さの上界
+/// 頂点の番号は1..=nで、parent[0]はダミー
+struct UnionFind {
+    parent: Vec<usize>,
+    rank: Vec<u32>,
+}
+
+impl UnionFind {
+    /// 頂点1..=nがそれぞれ1つずつの集合になっている状態で作る
+    fn new(n: usize) -> Self {
+        UnionFind {
+            parent: (0..=n).collect(),
+            rank: vec![0; n + 1],
+        }
+    }
+
+    /// vを含む集合の代表(根)を返す
+    /// たどった頂点は全て根を直接指すようにする(経路圧縮)
+    fn find(&mut self, v: usize) -> usize {
+        let mut root = v;
+        while self.parent[root] != root {
+            root = self.parent[root];
+        }
+        let mut x = v;
+        while self.parent[x] != root {
+            let next = self.parent[x];
+            self.parent[x] = root;
+            x = next;
+        }
+        root
+    }
+
+    /// uを含む集合とvを含む集合を合併する
+    /// 低い木を高い木の下につなぐ(ランクによる合併)
+    /// もともと同じ集合だったときはfalseを返す
+    fn union(&mut self, u: usize, v: usize) -> bool {
+        let (ru, rv) = (self.find(u), self.find(v));
+        if ru == rv {
+            return false;
+        }
+        match self.rank[ru].cmp(&self.rank[rv]) {
+            std::cmp::Ordering::Less => self.parent[ru] = rv,
+            std::cmp::Ordering::Greater => self.parent[rv] = ru,
+            std::cmp::Ordering::Equal => {
+                self.parent[rv] = ru;
+                self.rank[ru] += 1;
+            }
+        }
+        true
+    }
+}
+
+/// 辺の向きを無視したときの連結成分の数をUnion-Findで数える
+/// 辺 1..=m の両端を合併していき、最後に残った根の数を返す(孤立点も1つの成分)
+/// 接続リストを作らずに済むので、connected_componentsの結果の確認にも使える
+/// n: 頂点数
+/// m: 辺数
+fn count_components_union_find(edge: &EdgeList, n: usize, m: usize) -> usize {
+    let mut uf = UnionFind::new(n);
+    let mut count = n;
+    for a in 1..=m {
+        if uf.union(edge.tail[a], edge.head[a]) {
+            count -= 1;
+        }
+    }
+    count
+}
+
+/// 有向オイラー路(全ての辺をちょうど1回ずつ通る路)を辺の番号の列で返す
+/// 存在条件
+///   閉路: 全ての頂点で入次数 = 出次数
+///   路: 出次数 - 入次数 = 1 の頂点(始点)と -1 の頂点(終点)が1つずつで、残りは等しい
+///   さらに辺を持つ頂点が全て1つの路でつながっていること
+/// 閉路が存在するときは辺を持つ最小の番号の頂点から始める
+/// Hierholzerのアルゴリズムで、各頂点の接続リストをedge_first, edge_nextの順に使い切っていく
+/// 存在しなければNone
+/// n: 頂点数
+/// m: 辺数
+fn euler_path(edge: &EdgeList, graph: &DirectedGraph, n: usize, m: usize) -> Option<Vec<usize>> {
+    let (out_degree, in_degree) = degrees(edge, graph, n);
+    let mut start = 0;
+    let mut end_count = 0;
+    for v in 1..=n {
+        let (out_d, in_d) = (out_degree[v], in_degree[v]);
+        if out_d == in_d + 1 {
+            if start != 0 {
+                return None;
+            }
+            start = v;
+        } else if in_d == out_d + 1 {
+            end_count += 1;
+            if end_count > 1 {
+                return None;
+            }
+        } else if out_d != in_d {
+            return None;
+        }
+    }
+    if start == 0 {
+        if end_count != 0 {
+            return None;
+        }
+        match (1..=n).find(|&v| out_degree[v] > 0) {
+            Some(v) => start = v,
+            None => return Some(Vec::new()),
+        }
+    }
+
+    // next_edge[v] 頂点vから次に使う辺
+    let mut next_edge = graph.edge_first.clone();
+    // (頂点, その頂点に入るときに使った辺)
+    let mut stack = vec![(start, 0)];
+    let mut path = Vec::with_capacity(m);
+    while let Some(&(u, _)) = stack.last() {
+        let a = next_edge[u];
+        if a != 0 {
+            next_edge[u] = graph.edge_next[a];
+            stack.push((edge.head[a], a));
+        } else {
+            let (_, a) = stack.pop().unwrap();
+            if a != 0 {
+                path.push(a);
+            }
+        }
+    }
+
+    // 使い残した辺があれば、辺を持つ頂点がつながっていない
+    if path.len() != m {
+        return None;
+    }
+    path.reverse();
+    Some(path)
+}
+
+/// 多重辺(同じ始点と終点を持つ2本以上の辺)を列挙する
+/// (始点, 終点, その間の辺の番号の一覧) を (始点, 終点) の順に並べて返す
+/// 辺の番号の一覧は昇順。同じ頂点への自己ループが2本以上あればそれも含める
+/// m: 辺数
+fn parallel_edges(edge: &EdgeList, _n: usize, m: usize) -> Vec<(usize, usize, Vec<usize>)> {
+    let mut groups: BTreeMap<(usize, usize), Vec<usize>> = BTreeMap::new();
+    for a in 1..=m {
+        groups
+            .entry((edge.tail[a], edge.head[a]))
+            .or_default()
+            .push(a);
+    }
+    groups
+        .into_iter()
+        .filter(|(_, ids)| ids.len() >= 2)
+        .map(|((tail, head), ids)| (tail, head, ids))
+        .collect()
+}
+
+/// 多重辺を取り除いた単純グラフの辺リストを作る
+/// (始点, 終点) の組ごとに最初に現れた辺だけを元の順番のまま残す
+/// drop_self_loops が true なら自己ループも取り除く
+/// 返り値は新しい辺リストと辺数
+/// m: 辺数
+fn simplify(edge: &EdgeList, _n: usize, m: usize, drop_self_loops: bool) -> (EdgeList, usize) {
+    let mut result = EdgeList {
+        tail: vec![0],
+        head: vec![0],
+    };
+    let mut seen = HashSet::new();
+    for a in 1..=m {
+        let (u, w) = (edge.tail[a], edge.head[a]);
+        if drop_self_loops && u == w {
+            continue;
+        }
+        if seen.insert((u, w)) {
+            result.tail.push(u);
+            result.head.push(w);
+        }
+    }
+    let count = result.tail.len() - 1;
+    (result, count)
+}
+
+/// 最小共通祖先(LCA)を求めるためのダブリングの表
+/// depth[v] 根から頂点vまでの深さ(根は0、根から到達できない頂点は usize::MAX)
+/// up[k][v] vから2^k回親をたどった頂点(根より上は根のまま)
+struct LcaTable {
+    depth: Vec<usize>,
+    up: Vec<Vec<usize>>,
+}
+
+/// 頂点rootを根とする深さ優先探索木からLcaTableを作る
+/// 木は辺の向き(edge_first, edge_next)にたどって作るので、根から葉へ向いた辺で与える
+/// 表の段数は ceil(log2(n)) (ただし1段以上)
+/// n: 頂点数
+/// root: 根
+fn lca_preprocess(edge: &EdgeList, graph: &DirectedGraph, n: usize, root: usize) -> LcaTable {
+    let mut levels = 1;
+    while (1 << levels) < n {
+        levels += 1;
+    }
+
+    let mut depth = vec![usize::MAX; n + 1];
+    let mut parent = vec![0; n + 1];
+    depth[root] = 0;
+    parent[root] = root;
+    let mut stack = vec![root];
+    while let Some(u) = stack.pop() {
+        for a in out_edges(graph, u) {
+            let w = edge.head[a];
+            if depth[w] == usize::MAX {
+                depth[w] = depth[u] + 1;
+                parent[w] = u;
+                stack.push(w);
+            }
+        }
+    }
+
+    let mut up = vec![parent];
+    for k in 1..levels {
+        let prev = &up[k - 1];
+        let next = (0..=n).map(|v| prev[prev[v]]).collect();
+        up.push(next);
+    }
+    LcaTable { depth, up }
+}
+
+impl LcaTable {
+    /// 頂点uと頂点vの最小共通祖先
+    /// どちらかが根から到達できない頂点なら0を返す
+    fn query(&self, u: usize, v: usize) -> usize {
+        if self.depth[u] == usize::MAX || self.depth[v] == usize::MAX {
+            return 0;
+        }
+        let (mut u, mut v) = if self.depth[u] >= self.depth[v] {
+            (u, v)
+        } else {
+            (v, u)
+        };
+        // uをvと同じ深さまで持ち上げる
+        let diff = self.depth[u] - self.depth[v];
+        for (k, row) in self.up.iter().enumerate() {
+            if diff >> k & 1 == 1 {
+                u = row[u];
+            }
+        }
+        if u == v {
+            return u;
+        }
+        for row in self.up.iter().rev() {
+            if row[u] != row[v] {
+                u = row[u];
+                v = row[v];
+            }
+        }
+        self.up[0][u]
+    }
+}
+
+/// 幅優先探索で最短路の長さと直前の頂点を求める
+/// dist[v] sourceから頂点vへの最短路の辺数(到達できない頂点は usize::MAX)
+/// pred[v] sourceからvへの最短路でvの直前にある頂点(sourceと到達できない頂点は0)
+/// n: 頂点数
+/// source: 始点
+fn bfs_paths(
+    edge: &EdgeList,
+    graph: &DirectedGraph,
+    n: usize,
+    source: usize,
+) -> (Vec<usize>, Vec<usize>) {
+    let mut dist = vec![usize::MAX; n + 1];
+    let mut pred = vec![0; n + 1];
+    let mut queue = VecDeque::new();
+
+    dist[source] = 0;
+    queue.push_back(source);
+    while let Some(u) = queue.pop_front() {
+        for a in out_edges(graph, u) {
+            let w = edge.head[a];
+            if dist[w] == usize::MAX {
+                dist[w] = dist[u] + 1;
+                pred[w] = u;
+                queue.push_back(w);
+            }
+        }
+    }
+    (dist, pred)
+}
+
+/// bfs_pathsのpredをtargetから逆にたどり、sourceからtargetへの路を頂点の列で返す
+/// predだけでは始点と到達できない頂点の区別がつかないので始点sourceも渡す
+/// たどった先がsourceにならなければ(到達できなければ)None
+fn reconstruct_path(pred: &[usize], source: usize, target: usize) -> Option<Vec<usize>> {
+    let mut path = vec![target];
+    let mut v = target;
+    while v != source {
+        v = pred[v];
+        if v == 0 {
+            return None;
+        }
+        path.push(v);
+    }
+    path.reverse();
+    Some(path)
+}
+
+/// トポロジカル順の世代分け
+/// 第0世代は入次数0の頂点、第k世代は入ってくる辺の始点が全て第k-1世代以前にある頂点
+/// 同じ世代の頂点は互いに依存しないので並行して処理できる
+/// 各世代の頂点は番号の小さい順に並べる
+/// 閉路があればNone
+/// n: 頂点数
+fn topological_generations(
+    edge: &EdgeList,
+    graph: &DirectedGraph,
+    n: usize,
+) -> Option<Vec<Vec<usize>>> {
+    let (_, mut in_degree) = degrees(edge, graph, n);
+    let mut current: Vec<usize> = (1..=n).filter(|&v| in_degree[v] == 0).collect();
+    let mut generations = Vec::new();
+    let mut count = 0;
+
+    while !current.is_empty() {
+        let mut next = Vec::new();
+        for &u in &current {
+            for a in out_edges(graph, u) {
+                let w = edge.head[a];
+                in_degree[w] -= 1;
+                if in_degree[w] == 0 {
+                    next.push(w);
+                }
+            }
+        }
+        next.sort_unstable();
+        count += current.len();
+        generations.push(current);
+        current = next;
+    }
+
+    (count == n).then_some(generations)
+}
+
+/// 各頂点から出る辺の終点を1行ずつ `v -> [w1, w2, ...]` の形で並べた文字列
+/// 終点はedge_first, edge_nextの鎖の順で、辺のない頂点は `v -> []` になる
+/// n: 頂点数
+fn describe(edge: &EdgeList, graph: &DirectedGraph, n: usize) -> String {
+    let mut text = String::new();
+    for v in 1..=n {
+        let heads: Vec<usize> = out_edges(graph, v).map(|a| edge.head[a]).collect();
+        writeln!(text, "{v} -> {heads:?}").unwrap();
+    }
+    text
+}
+
+/// 深さ制限付きの深さ優先探索
+/// sourceから max_depth 本以内の辺で到達できる頂点だけを調べる
+/// depth[v] 頂点vを見つけた最も浅い深さ(制限内で到達できない頂点は usize::MAX)
+/// 深い位置で先に見つけた頂点も、後からより浅い路で見つかれば深さを更新してそこから探索し直す
+/// n: 頂点数
+/// source: 始点
+/// max_depth: たどる辺の数の上限
+fn dfs_depth_limited(
+    edge: &EdgeList,
+    graph: &DirectedGraph,
+    n: usize,
+    source: usize,
+    max_depth: usize,
+) -> Vec<usize> {
+    let mut depth = vec![usize::MAX; n + 1];
+    depth[source] = 0;
+    let mut stack = vec![source];
+    while let Some(u) = stack.pop() {
+        let d = depth[u] + 1;
+        if d > max_depth {
+            continue;
+        }
+        for a in out_edges(graph, u) {
+            let w = edge.head[a];
+            if d < depth[w] {
+                depth[w] = d;
+                stack.push(w);
+            }
+        }
+    }
+    depth
+}
+
+/// 辺の向きを無視したときに全体が1つにつながっているか(弱連結か)
+/// 頂点1から向きを無視して幅優先探索を行い、全ての頂点に届いた時点でtrueを返す
+/// 孤立点が1つでもあれば(n >= 2 のとき)false。頂点がなければ成分もないのでfalse
+/// n: 頂点数
+fn is_weakly_connected(edge: &EdgeList, graph: &DirectedGraph, n: usize) -> bool {
+    if n == 0 {
+        return false;
+    }
+    let mut visited = vec![false; n + 1];
+    let mut queue = VecDeque::new();
+    visited[1] = true;
+    queue.push_back(1);
+    let mut count = 1;
+    while let Some(u) = queue.pop_front() {
+        if count == n {
+            return true;
+        }
+        for (_, w) in undirected_edges(edge, graph, u) {
+            if !visited[w] {
+                visited[w] = true;
+                count += 1;
+                queue.push_back(w);
+            }
+        }
+    }
+    count == n
+}
+
+/// どの頂点からどの頂点へも有向路があるか(強連結か)
+/// 頂点1から辺の向きにたどった探索と、逆向き(rev_edge_first, rev_edge_next)にたどった探索の
+/// 両方が全ての頂点に届けば強連結
+/// 頂点がなければfalse
+/// n: 頂点数
+fn is_strongly_connected(edge: &EdgeList, graph: &DirectedGraph, n: usize) -> bool {
+    n > 0
+        && reaches_all(n, &graph.edge_first, &graph.edge_next, &edge.head)
+        && reaches_all(n, &graph.rev_edge_first, &graph.rev_edge_next, &edge.tail)
+}
+
+/// 頂点1から鎖 first, next をたどって、全ての頂点に届くかどうか
+/// ends[a] は辺aをたどった先の頂点(順向きならhead、逆向きならtail)
+fn reaches_all(n: usize, first: &[usize], next: &[usize], ends: &[usize]) -> bool {
+    let mut visited = vec![false; n + 1];
+    visited[1] = true;
+    let mut count = 1;
+    let mut stack = vec![1];
+    while let Some(u) = stack.pop() {
+        let mut a = first[u];
+        while a != 0 {
+            let w = ends[a];
+            if !visited[w] {
+                visited[w] = true;
+                count += 1;
+                stack.push(w);
+            }
+            a = next[a];
+        }
+    }
+    count == n
+}
+
+/// 辺ごとに利用者のデータを持たせた辺リスト
+/// data[a] 辺aのデータ(先頭のdata[0]はダミー)
+/// EdgeListへのDerefを実装しているので、&EdgeListを受け取る関数にそのまま渡せる
+/// (EdgeListを EdgeListData<()> の別名にすると、EdgeList { tail, head } と書いている箇所全てに
+/// dataが必要になり、&EdgeListを受け取る関数も全て総称型にしなければならないので、包む形にしている)
+struct EdgeListData<T> {
+    edge: EdgeList,
+    data: Vec<T>,
+}
+
+impl<T> EdgeListData<T> {
+    /// 辺のない状態で作る。dummyはdata[0]に入れる値
+    fn new(dummy: T) -> Self {
+        EdgeListData {
+            edge: EdgeList {
+                tail: vec![0],
+                head: vec![0],
+            },
+            data: vec![dummy],
+        }
+    }
+
+    /// データdataを持つ辺 tail -> head を追加し、その辺の番号を返す
+    fn push(&mut self, tail: usize, head: usize, data: T) -> usize {
+        self.edge.tail.push(tail);
+        self.edge.head.push(head);
+        self.data.push(data);
+        self.data.len() - 1
+    }
+
+    /// 辺aのデータ
+    fn data(&self, a: usize) -> &T {
+        &self.data[a]
+    }
+}
+
+impl<T> Deref for EdgeListData<T> {
+    type Target = EdgeList;
+
+    fn deref(&self) -> &EdgeList {
+        &self.edge
+    }
+}
+
+/// Kruskal法による最小全域木
+/// 辺の向きは無視し、重みの小さい辺から順に、閉路を作らない(両端が別の集合にある)ものを選ぶ
+/// 重みが等しい辺は番号の小さい方を先に調べる
+/// 返り値は選んだ辺の重みの合計と、選んだ辺の番号(選んだ順)
+/// 連結でないときは各連結成分の最小全域木を合わせた最小全域森になる
+/// weight[a] 辺aの重み(先頭にダミーの0を入れ、辺の番号と揃える)
+/// n: 頂点数
+/// m: 辺数
+fn kruskal(edge: &EdgeList, weight: &[i64], n: usize, m: usize) -> (i64, Vec<usize>) {
+    let mut order: Vec<usize> = (1..=m).collect();
+    order.sort_by_key(|&a| (weight[a], a));
+
+    let mut uf = UnionFind::new(n);
+    let mut total = 0;
+    let mut chosen = Vec::new();
+    for a in order {
+        if uf.union(edge.tail[a], edge.head[a]) {
+            total += weight[a];
+            chosen.push(a);
+        }
+    }
+    (total, chosen)
+}
+
+/// Prim法による最小全域木
+/// 有向グラフの辺を無向とみなすため、各頂点から出る辺と入る辺の両方をたどる
+/// sourceだけの木から始め、木と木の外を結ぶ辺のうち重みが最小のものを二分ヒープで選んで木を広げる
+/// 返り値は選んだ辺の重みの合計と、選んだ辺の番号(選んだ順)
+/// sourceを含む連結成分だけの最小全域木になる
+/// weight[a] 辺aの重み(先頭にダミーの0を入れ、辺の番号と揃える)
+/// n: 頂点数
+/// source: 始点
+fn prim(
+    edge: &EdgeList,
+    graph: &DirectedGraph,
+    weight: &[i64],
+    n: usize,
+    source: usize,
+) -> (i64, Vec<usize>) {
+    let mut in_tree = vec![false; n + 1];
+    // (重み, 辺の番号, 木の外の端点) の最小ヒープ
+    let mut heap = BinaryHeap::new();
+    let mut total = 0;
+    let mut chosen = Vec::new();
+
+    in_tree[source] = true;
+    for (a, w) in undirected_edges(edge, graph, source) {
+        heap.push(Reverse((weight[a], a, w)));
+    }
+    while let Some(Reverse((wt, a, u))) = heap.pop() {
+        if in_tree[u] {
+            continue;
+        }
+        in_tree[u] = true;
+        total += wt;
+        chosen.push(a);
+        for (b, w) in undirected_edges(edge, graph, u) {
+            if !in_tree[w] {
+                heap.push(Reverse((weight[b], b, w)));
+            }
+        }
+    }
+    (total, chosen)
+}
+
+/// 2部グラフの最大マッチング(Hopcroft–Karpのアルゴリズム)
+/// pairs[i] = (l, r) は左側の頂点l (1..=n_left) と右側の頂点r (1..=n_right) を結ぶ辺
+/// 内部では右側の頂点rを n_left + r として、左から右へ向いた辺リストと接続リストを作る
+/// 幅優先探索で自由な左頂点からの層を、自由な右頂点に届く最初の層まで作り、
+/// その層に沿った深さ優先探索(非再帰)で互いに素な最短の増加路をまとめて見つけることを繰り返す
+/// 段階の数は O(√n) なので、全体で O(m √n)
+/// 返り値はマッチングに使った (l, r) の組を左の頂点の番号順に並べたもの
+fn bipartite_matching(
+    pairs: &[(usize, usize)],
+    n_left: usize,
+    n_right: usize,
+) -> Vec<(usize, usize)> {
+    let n = n_left + n_right;
+    let edge = EdgeList {
+        tail: std::iter::once(0)
+            .chain(pairs.iter().map(|&(l, _)| l))
+            .collect(),
+        head: std::iter::once(0)
+            .chain(pairs.iter().map(|&(_, r)| n_left + r))
+            .collect(),
+    };
+    let graph = dicomp_incidence_list_construct(&edge, n, pairs.len());
+
+    // mate[v] vとマッチしている頂点(いなければ0)。左右どちらもn頂点の番号で持つ
+    let mut mate = vec![0; n + 1];
+    // dist[l] 幅優先探索で求めた左頂点lの層
+    let mut dist = vec![usize::MAX; n_left + 1];
+
+    /// 自由な左頂点rootから層に沿って増加路を探し、見つかればマッチングを入れ替える(非再帰)
+    /// limit: 自由な右頂点に隣接する左頂点の層(最短の増加路の長さで決まる)
+    /// フレームは (左頂点u, uから今調べている辺)。増加路が見つかったときは各フレームの辺が路になる
+    fn augment(
+        root: usize,
+        limit: usize,
+        edge: &EdgeList,
+        graph: &DirectedGraph,
+        mate: &mut [usize],
+        dist: &mut [usize],
+    ) -> bool {
+        let mut stack = vec![(root, graph.edge_first[root])];
+        while let Some(frame) = stack.last_mut() {
+            let (u, a) = *frame;
+            if a == 0 {
+                // 行き止まりの頂点はこの段階で二度と調べない
+                dist[u] = usize::MAX;
+                stack.pop();
+                if let Some(parent) = stack.last_mut() {
+                    parent.1 = graph.edge_next[parent.1];
+                }
+                continue;
+            }
+            let next = mate[edge.head[a]];
+            if next == 0 && dist[u] == limit {
+                for &(u, a) in &stack {
+                    let r = edge.head[a];
+                    mate[u] = r;
+                    mate[r] = u;
+                }
+                return true;
+            }
+            if next != 0 && dist[u] < limit && dist[next] == dist[u] + 1 {
+                stack.push((next, graph.edge_first[next]));
+            } else {
+                frame.1 = graph.edge_next[a];
+            }
+        }
+        false
+    }
+
+    loop {
+        let mut queue = VecDeque::new();
+        for l in 1..=n_left {
+            if mate[l] == 0 {
+                dist[l] = 0;
+                queue.push_back(l);
+            } else {
+                dist[l] = usize::MAX;
+            }
+        }
+        // 自由な右頂点が見つかった層より先は広げない
+        let mut limit = usize::MAX;
+        while let Some(u) = queue.pop_front() {
+            if dist[u] >= limit {
+                continue;
+            }
+            for a in out_edges(&graph, u) {
+                let next = mate[edge.head[a]];
+                if next == 0 {
+                    limit = dist[u];
+                } else if dist[next] == usize::MAX && dist[u] < limit {
+                    dist[next] = dist[u] + 1;
+                    queue.push_back(next);
+                }
+            }
+        }
+        if limit == usize::MAX {
+            break;
+        }
+        for l in 1..=n_left {
+            if mate[l] == 0 {
+                augment(l, limit, &edge, &graph, &mut mate, &mut dist);
+            }
+        }
+    }
+
+    (1..=n_left)
+        .filter(|&l| mate[l] != 0)
+        .map(|l| (l, mate[l] - n_left))
+        .collect()
+}
+
+/// 貪欲彩色
+/// orderの順に頂点を見て、既に色を塗った隣接頂点(辺の向きは無視する)が使っていない最小の色を塗る
+/// color[v] 頂点vの色(1から始まる。orderに含まれない頂点は0のまま)
+/// 自己ループは無視する
+/// order: 頂点を塗る順番
+/// n: 頂点数
+fn greedy_color(edge: &EdgeList, graph: &DirectedGraph, n: usize, order: &[usize]) -> Vec<usize> {
+    let mut color = vec![0; n + 1];
+    // used[c] == v なら色cはvの隣接頂点が使っている
+    let mut used = vec![0; n + 2];
+    for &v in order {
+        for (_, w) in undirected_edges(edge, graph, v) {
+            if w != v {
+                used[color[w]] = v;
+            }
+        }
+        color[v] = (1..).find(|&c| used[c] != v).unwrap();
+    }
+    color
+}
+
+/// 次数の大きい頂点から順に塗る貪欲彩色(Welsh–Powell)
+/// 次数は入次数と出次数の和で、等しいときは番号の小さい頂点を先に塗る
+/// n: 頂点数
+fn greedy_color_by_degree(edge: &EdgeList, graph: &DirectedGraph, n: usize) -> Vec<usize> {
+    let (out_degree, in_degree) = degrees(edge, graph, n);
+    let mut order: Vec<usize> = (1..=n).collect();
+    order.sort_by_key(|&v| (Reverse(out_degree[v] + in_degree[v]), v));
+    greedy_color(edge, graph, n, &order)
+}
+
+/// PageRank(べき乗法)
+/// 各反復で頂点uのランクを damping * rank[u] / out_degree[u] ずつ出る辺の終点へ配り、
+/// 残りの (1 - damping) は全頂点に均等に配る
+/// 出る辺のない頂点(dangling node)のランクは全頂点に均等に配り直す
+/// 初期値は全頂点 1/n で、返り値は長さ n+1 (rank[0]はダミーの0) で rank[1..] の和は1
+/// n: 頂点数
+/// m: 辺数
+/// damping: 減衰率(よく使われる値は0.85)
+/// iterations: 反復回数
+fn pagerank(
+    edge: &EdgeList,
+    graph: &DirectedGraph,
+    n: usize,
+    _m: usize,
+    damping: f64,
+    iterations: usize,
+) -> Vec<f64> {
+    if n == 0 {
+        return vec![0.0];
+    }
+    let (out_degree, _) = degrees(edge, graph, n);
+    let uniform = 1.0 / n as f64;
+    let mut rank = vec![uniform; n + 1];
+    rank[0] = 0.0;
+
+    for _ in 0..iterations {
+        let dangling: f64 = (1..=n)
+            .filter(|&u| out_degree[u] == 0)
+            .map(|u| rank[u])
+            .sum();
+        let base = (1.0 - damping) * uniform + damping * dangling * uniform;
+        let mut next = vec![base; n + 1];
+        next[0] = 0.0;
+        for u in (1..=n).filter(|&u| out_degree[u] > 0) {
+            let share = damping * rank[u] / out_degree[u] as f64;
+            for a in out_edges(graph, u) {
+                next[edge.head[a]] += share;
+            }
+        }
+        rank = next;
+    }
+    rank
+}
+
+/// k-コア分解
+/// core[v] 頂点vのコア数(vを含み、全ての頂点の次数がk以上になる部分グラフが存在する最大のk)
+/// 次数は辺の向きを無視して数え(出る辺と入る辺の和)、自己ループは数えない
+/// 次数が最小の頂点を取り除き、その隣接頂点の次数を減らすことを繰り返す
+/// 取り除いたときの次数のそれまでの最大値がその頂点のコア数になる
+/// n: 頂点数
+fn k_core(edge: &EdgeList, graph: &DirectedGraph, n: usize) -> Vec<usize> {
+    let mut degree = vec![0; n + 1];
+    for (v, d) in degree.iter_mut().enumerate().skip(1) {
+        *d = undirected_edges(edge, graph, v)
+            .filter(|&(_, w)| w != v)
+            .count();
+    }
+    let mut heap: BinaryHeap<Reverse<(usize, usize)>> =
+        (1..=n).map(|v| Reverse((degree[v], v))).collect();
+    let mut removed = vec![false; n + 1];
+    let mut core = vec![0; n + 1];
+    let mut k = 0;
+
+    while let Some(Reverse((d, v))) = heap.pop() {
+        // 次数が減る前に積んだ古い要素は読み飛ばす
+        if removed[v] || d != degree[v] {
+            continue;
+        }
+        removed[v] = true;
+        k = k.max(d);
+        core[v] = k;
+        for (_, w) in undirected_edges(edge, graph, v) {
+            if !removed[w] {
+                degree[w] -= 1;
+                heap.push(Reverse((degree[w], w)));
+            }
+        }
+    }
+    core
+}
+
+/// 幅優先探索木(最短路木)を辺リストとして取り出す
+/// 各頂点を最初に発見した辺だけを、発見した順に並べる
+/// 到達できない頂点に対応する辺はない
+/// 返り値は木の辺リストと辺数
+/// n: 頂点数
+/// source: 始点(木の根)
+fn bfs_tree(edge: &EdgeList, graph: &DirectedGraph, n: usize, source: usize) -> (EdgeList, usize) {
+    let mut tree = EdgeList {
+        tail: vec![0],
+        head: vec![0],
+    };
+    let mut visited = vec![false; n + 1];
+    let mut queue = VecDeque::new();
+    visited[source] = true;
+    queue.push_back(source);
+    while let Some(u) = queue.pop_front() {
+        for a in out_edges(graph, u) {
+            let w = edge.head[a];
+            if !visited[w] {
+                visited[w] = true;
+                tree.tail.push(u);
+                tree.head.push(w);
+                queue.push_back(w);
+            }
+        }
+    }
+    let m = tree.tail.len() - 1;
+    (tree, m)
+}
+
+/// 自己ループ(tail[a] == head[a] となる辺a)の番号を昇順に返す
+/// m: 辺数
+fn self_loops(edge: &EdgeList, m: usize) -> Vec<usize> {
+    (1..=m).filter(|&a| edge.tail[a] == edge.head[a]).collect()
+}
+
+/// 自己ループの本数
+/// m: 辺数
+fn count_self_loops(edge: &EdgeList, m: usize) -> usize {
+    (1..=m).filter(|&a| edge.tail[a] == edge.head[a]).count()
+}
+
+/// 自己ループを取り除いた辺リストを作る
+/// 残りの辺は元の順番のまま番号を詰める
+/// 返り値は新しい辺リストと辺数
+/// m: 辺数
+fn remove_self_loops(edge: &EdgeList, m: usize) -> (EdgeList, usize) {
+    let mut result = EdgeList {
+        tail: vec![0],
+        head: vec![0],
+    };
+    for a in (1..=m).filter(|&a| edge.tail[a] != edge.head[a]) {
+        result.tail.push(edge.tail[a]);
+        result.head.push(edge.head[a]);
+    }
+    let count = result.tail.len() - 1;
+    (result, count)
+}
+
+/// 有向非巡回グラフ(DAG)の最長路(重み最大の路)
+/// トポロジカル順に頂点を処理し、出ていく辺を緩和する
+/// 路はどの頂点から始めてもよい(辺を含まない路の長さは0)
+/// 返り値は最長路の長さとその頂点列。閉路があるときは None
+/// weight[a] 辺aの重み(先頭にダミーの0を入れ、辺の番号と揃える)
+/// n: 頂点数
+fn longest_path_dag(
+    edge: &EdgeList,
+    graph: &DirectedGraph,
+    weight: &[i64],
+    n: usize,
+) -> Option<(i64, Vec<usize>)> {
+    let order = topological_sort(edge, graph, n)?;
+    if n == 0 {
+        return Some((0, Vec::new()));
+    }
+
+    // dist[v] 頂点vで終わる路の最大の長さ
+    // pred[v] その路でvの直前の頂点(路の始点なら0)
+    let mut dist = vec![0; n + 1];
+    let mut pred = vec![0; n + 1];
+    for &u in &order {
+        for a in out_edges(graph, u) {
+            let w = edge.head[a];
+            if dist[u] + weight[a] > dist[w] {
+                dist[w] = dist[u] + weight[a];
+                pred[w] = u;
+            }
+        }
+    }
+
+    let mut last = 1;
+    for v in 2..=n {
+        if dist[v] > dist[last] {
+            last = v;
+        }
+    }
+    let mut path = vec![last];
+    while pred[path[path.len() - 1]] != 0 {
+        path.push(pred[path[path.len() - 1]]);
+    }
+    path.reverse();
+    Some((dist[last], path))
+}
+
+/// 辺を1本ずつ追加しながら、辺の向きを無視したときの連結成分の数を追跡する
+/// 接続リストを作り直さずに済むので、辺が逐次届く場合に使う
+/// count 現在の連結成分の数
+struct IncrementalForest {
+    uf: UnionFind,
+    count: usize,
+}
+
+impl IncrementalForest {
+    /// 頂点1..=nが全て孤立点の状態で作る
+    fn new(n: usize) -> Self {
+        IncrementalForest {
+            uf: UnionFind::new(n),
+            count: n,
+        }
+    }
+
+    /// 辺 u - v を追加する
+    /// 異なる2つの成分がつながったときはtrueを返し、成分の数を1減らす
+    fn add_edge(&mut self, u: usize, v: usize) -> bool {
+        let joined = self.uf.union(u, v);
+        if joined {
+            self.count -= 1;
+        }
+        joined
+    }
+
+    /// 現在の連結成分の数
+    fn component_count(&self) -> usize {
+        self.count
+    }
+}
+
+/// 入ってくる辺のない頂点(湧き出し口)を番号の小さい順に返す
+/// 自己ループのある頂点は入次数を持つので含まれない
+/// n: 頂点数
+fn sources(_edge: &EdgeList, graph: &DirectedGraph, n: usize) -> Vec<usize> {
+    (1..=n).filter(|&v| graph.rev_edge_first[v] == 0).collect()
+}
+
+/// 出ていく辺のない頂点(吸い込み口)を番号の小さい順に返す
+/// 自己ループのある頂点は出次数を持つので含まれない
+/// n: 頂点数
+fn sinks(_edge: &EdgeList, graph: &DirectedGraph, n: usize) -> Vec<usize> {
+    (1..=n).filter(|&v| graph.edge_first[v] == 0).collect()
+}
+
+/// 深さ優先探索で、各頂点を発見したときの親も記録する
+/// ラベルはdfs_iterativeと同じ
+/// parent[v] 頂点vを最初に発見したときにたどった辺の始点(始点と到達できない頂点は0)
+/// n: 頂点数
+/// source: 探索の始点(1..=nであること)
+fn dfs_with_parent(
+    edge: &EdgeList,
+    graph: &DirectedGraph,
+    n: usize,
+    source: usize,
+) -> (DfsTime, Vec<usize>) {
+    debug_assert!(
+        (1..=n).contains(&source),
+        "start vertex {source} is out of range 1..={n}"
+    );
+    let mut time = DfsTime {
+        pre_label: vec![0; n + 1],
+        post_label: vec![0; n + 1],
+    };
+    let mut parent = vec![0; n + 1];
+
+    // 先行順のラベル
+    let mut k: usize = 1;
+    // 後行順のラベル
+    let mut j: usize = 1;
+
+    time.pre_label[source] = k;
+    k += 1;
+    let mut stack = vec![(source, graph.edge_first[source])];
+    while let Some(frame) = stack.last_mut() {
+        let (u, a) = *frame;
+        if a == 0 {
+            stack.pop();
+            time.post_label[u] = j;
+            j += 1;
+            continue;
+        }
+        frame.1 = graph.edge_next[a];
+        let w = edge.head[a];
+        if time.pre_label[w] == 0 {
+            time.pre_label[w] = k;
+            k += 1;
+            parent[w] = u;
+            stack.push((w, graph.edge_first[w]));
+        }
+    }
+    (time, parent)
+}
+
+/// 既存の接続リストに辺 tail -> head を1本追加し、新しい辺の番号を返す
+/// dicomp_incidence_list_constructの説明にある手順そのままに、
+/// 新しい辺を順方向・逆方向の両方の鎖の先頭につなぐ(O(1))
+/// そのため追加した辺は、その頂点の既存の辺より先にたどられる
+/// 頂点の数は変えないので、tail, headは既存の頂点であること
+fn add_edge_to_graph(
+    graph: &mut DirectedGraph,
+    edge: &mut EdgeList,
+    tail: usize,
+    head: usize,
+) -> usize {
+    edge.tail.push(tail);
+    edge.head.push(head);
+    let a = edge.tail.len() - 1;
+
+    graph.edge_next.push(graph.edge_first[tail]);
+    graph.edge_first[tail] = a;
+    graph.rev_edge_next.push(graph.rev_edge_first[head]);
+    graph.rev_edge_first[head] = a;
+    a
+}
+
+/// 各頂点の離心数(その頂点から到達できる頂点までの最短路の辺数の最大値)
+/// 全ての頂点から幅優先探索を行う
+/// 到達できない頂点は無視する(usize::MAXにはしない)ので、出ていく辺のない頂点の離心数は0
+/// ecc[0]はダミーの0
+/// n: 頂点数
+fn eccentricities(edge: &EdgeList, graph: &DirectedGraph, n: usize) -> Vec<usize> {
+    let mut ecc = vec![0; n + 1];
+    for (v, e) in ecc.iter_mut().enumerate().skip(1) {
+        *e = bfs(edge, graph, n, v)
+            .into_iter()
+            .filter(|&d| d != usize::MAX)
+            .max()
+            .unwrap_or(0);
+    }
+    ecc
+}
+
+/// 直径(離心数の最大値)
+/// eccentricitiesと同じく到達できない頂点の組は無視する。n = 0 のときは0
+/// n: 頂点数
+fn diameter(edge: &EdgeList, graph: &DirectedGraph, n: usize) -> usize {
+    eccentricities(edge, graph, n)
+        .into_iter()
+        .max()
+        .unwrap_or(0)
+}
+
+/// 頂点vから出る辺の終点(後続頂点)を接続リストの順に返す
+/// 多重辺があるときは同じ頂点がその本数だけ並ぶ
+fn neighbors(edge: &EdgeList, graph: &DirectedGraph, v: usize) -> Vec<usize> {
+    out_edges(graph, v).map(|a| edge.head[a]).collect()
+}
+
+/// 頂点vに入る辺の始点(先行頂点)を逆向きの接続リストの順に返す
+/// 多重辺があるときは同じ頂点がその本数だけ並ぶ
+fn predecessors(edge: &EdgeList, graph: &DirectedGraph, v: usize) -> Vec<usize> {
+    in_edges(graph, v).map(|a| edge.tail[a]).collect()
+}
+
+/// dfs_visitが訪問者に渡す出来事
+/// Discover(v): 頂点vを初めて訪問した(先行順)
+/// Finish(v): 頂点vから出る辺を全て調べ終えた(後行順)
+/// TreeEdge(a): 辺aで未訪問の頂点を発見した(この直後にその頂点のDiscoverが来る)
+/// BackEdge(a): 辺aが探索中の頂点(祖先)へ戻る。自己ループを含む
+/// ForwardOrCrossEdge(a): 辺aが探索済みの頂点へ向かう
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Event {
+    Discover(usize),
+    Finish(usize),
+    TreeEdge(usize),
+    BackEdge(usize),
+    ForwardOrCrossEdge(usize),
+}
+
+/// 訪問者(visitor)を受け取る深さ優先探索
+/// 探索の順番はdfs_iterativeと同じで、出来事が起きるたびにvisitorを呼ぶ
+/// visitorが ControlFlow::Break(()) を返すとそこで探索を打ち切り、Breakを返す
+/// 最後まで探索したときは ControlFlow::Continue(()) を返す
+/// n: 頂点数
+/// source: 探索の始点(1..=nであること)
+fn dfs_visit<F: FnMut(Event) -> ControlFlow<()>>(
+    edge: &EdgeList,
+    graph: &DirectedGraph,
+    n: usize,
+    source: usize,
+    mut visitor: F,
+) -> ControlFlow<()> {
+    debug_assert!(
+        (1..=n).contains(&source),
+        "start vertex {source} is out of range 1..={n}"
+    );
+    let mut color = vec![Color::White; n + 1];
+    color[source] = Color::Gray;
+    visitor(Event::Discover(source))?;
+    let mut stack = vec![(source, graph.edge_first[source])];
+    while let Some(frame) = stack.last_mut() {
+        let (u, a) = *frame;
+        if a == 0 {
+            stack.pop();
+            color[u] = Color::Black;
+            visitor(Event::Finish(u))?;
+            continue;
+        }
+        frame.1 = graph.edge_next[a];
+        let w = edge.head[a];
+        match color[w] {
+            Color::White => {
+                visitor(Event::TreeEdge(a))?;
+                color[w] = Color::Gray;
+                visitor(Event::Discover(w))?;
+                stack.push((w, graph.edge_first[w]));
+            }
+            Color::Gray => visitor(Event::BackEdge(a))?,
+            Color::Black => visitor(Event::ForwardOrCrossEdge(a))?,
+        }
+    }
+    ControlFlow::Continue(())
+}
+
+/// 深さ優先探索でsourceからtargetへ到達できるかを調べる
+/// targetを発見した時点で探索を打ち切り、ラベルの配列は作らない
+/// 探索の開始時点でsourceは発見済みなので、source == target なら常にtrue
+/// (長さ1以上の路を求めるcan_reachとはここが異なる)
+/// n: 頂点数
+fn dfs_find(
+    edge: &EdgeList,
+    graph: &DirectedGraph,
+    n: usize,
+    source: usize,
+    target: usize,
+) -> bool {
+    if source == target {
+        return true;
+    }
+    let mut visited = vec![false; n + 1];
+    visited[source] = true;
+    let mut stack = vec![(source, graph.edge_first[source])];
+    while let Some(frame) = stack.last_mut() {
+        let (_, a) = *frame;
+        if a == 0 {
+            stack.pop();
+            continue;
+        }
+        frame.1 = graph.edge_next[a];
+        let w = edge.head[a];
+        if w == target {
+            return true;
+        }
+        if !visited[w] {
+            visited[w] = true;
+            stack.push((w, graph.edge_first[w]));
+        }
+    }
+    false
+}
+
+/// 幅優先探索でsourceからtargetへ到達できるかを調べる
+/// sourceに近い頂点から順に調べ、targetを発見した時点で打ち切る
+/// source == target なら常にtrue
+/// n: 頂点数
+fn bfs_find(
+    edge: &EdgeList,
+    graph: &DirectedGraph,
+    n: usize,
+    source: usize,
+    target: usize,
+) -> bool {
+    if source == target {
+        return true;
+    }
+    let mut visited = vec![false; n + 1];
+    let mut queue = VecDeque::new();
+    visited[source] = true;
+    queue.push_back(source);
+    while let Some(u) = queue.pop_front() {
+        for a in out_edges(graph, u) {
+            let w = edge.head[a];
+            if w == target {
+                return true;
+            }
+            if !visited[w] {
+                visited[w] = true;
+                queue.push_back(w);
+            }
+        }
+    }
+    false
+}
+
+/// DIMACS形式のグラフを読み込む
+///   c ...      コメント行
+///   p edge n m 問題行(頂点数n, 辺数m)。辺より前に1回だけ書く
+///   e u v      辺 u -> v
+/// 空行は読み飛ばす。`p`行の辺数と`e`行の数が一致しなければエラー
+/// `p`行がないときのMissingProblemLineの行番号は、最後の行の次の番号にする
+/// 返り値は先頭にダミーの0を入れた辺リストと、頂点数n, 辺数m
+fn from_dimacs(input: &str) -> Result<(EdgeList, usize, usize), ParseError> {
+    fn number(token: &str, line: usize) -> Result<usize, ParseError> {
+        token.parse().map_err(|_| ParseError::InvalidToken {
+            line,
+            token: token.to_string(),
+        })
+    }
+
+    let mut edge = EdgeList {
+        tail: vec![0],
+        head: vec![0],
+    };
+    // (頂点数, 辺数)
+    let mut problem: Option<(usize, usize)> = None;
+    let mut last_line = 0;
+
+    for (i, raw) in input.lines().enumerate() {
+        let line = i + 1;
+        last_line = line;
+        let tokens: Vec<&str> = raw.split_whitespace().collect();
+        match tokens.first() {
+            None | Some(&"c") => {}
+            Some(&"p") => {
+                if problem.is_some() {
+                    return Err(ParseError::UnexpectedLine { line });
+                }
+                if tokens.len() != 4 {
+                    return Err(ParseError::WrongTokenCount { line });
+                }
+                if tokens[1] != "edge" {
+                    return Err(ParseError::InvalidToken {
+                        line,
+                        token: tokens[1].to_string(),
+                    });
+                }
+                problem = Some((number(tokens[2], line)?, number(tokens[3], line)?));
+            }
+            Some(&"e") => {
+                let Some((n, _)) = problem else {
+                    return Err(ParseError::MissingProblemLine { line });
+                };
+                if tokens.len() != 3 {
+                    return Err(ParseError::WrongTokenCount { line });
+                }
+                let mut ends = [0; 2];
+                for (end, token) in ends.iter_mut().zip(&tokens[1..]) {
+                    let vertex = number(token, line)?;
+                    if vertex == 0 || vertex > n {
+                        return Err(ParseError::VertexOutOfRange { line, vertex });
+                    }
+                    *end = vertex;
+                }
+                edge.tail.push(ends[0]);
+                edge.head.push(ends[1]);
+            }
+            Some(_) => return Err(ParseError::UnexpectedLine { line }),
+        }
+    }
+
+    let Some((n, m)) = problem else {
+        return Err(ParseError::MissingProblemLine {
+            line: last_line + 1,
+        });
+    };
+    let found = edge.tail.len() - 1;
+    if found != m {
+        return Err(ParseError::EdgeCountMismatch { expected: m, found });
+    }
+    Ok((edge, n, m))
+}
+
+/// 強連結成分の数
+/// tarjanで成分が確定するたびに数えるだけで、成分番号の配列は作らない
+/// n: 頂点数
+fn count_sccs(edge: &EdgeList, graph: &DirectedGraph, n: usize) -> usize {
+    let mut count = 0;
+    tarjan(edge, graph, n, |_| count += 1);
+    count
+}
+
+/// 辺をたどる向き
+/// Forward: 辺の向き(edge_first, edge_next をたどり、headへ進む)
+/// Reverse: 辺の逆向き(rev_edge_first, rev_edge_next をたどり、tailへ進む)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Direction {
+    Forward,
+    Reverse,
+}
+
+/// 向きを指定した深さ優先探索
+/// Forwardならdfs_iterativeと同じ結果、Reverseなら転置グラフでの深さ優先探索と同じ結果になる
+/// n: 頂点数
+/// source: 探索の始点(1..=nであること)
+fn dfs_direction(
+    edge: &EdgeList,
+    graph: &DirectedGraph,
+    n: usize,
+    source: usize,
+    direction: Direction,
+) -> DfsTime {
+    debug_assert!(
+        (1..=n).contains(&source),
+        "start vertex {source} is out of range 1..={n}"
+    );
+    // ends[a] は辺aをたどった先の頂点
+    let (first, next, ends) = match direction {
+        Direction::Forward => (&graph.edge_first, &graph.edge_next, &edge.head),
+        Direction::Reverse => (&graph.rev_edge_first, &graph.rev_edge_next, &edge.tail),
+    };
+    let mut time = DfsTime {
+        pre_label: vec![0; n + 1],
+        post_label: vec![0; n + 1],
+    };
+
+    // 先行順のラベル
+    let mut k: usize = 1;
+    // 後行順のラベル
+    let mut j: usize = 1;
+
+    time.pre_label[source] = k;
+    k += 1;
+    let mut stack = vec![(source, first[source])];
+    while let Some(frame) = stack.last_mut() {
+        let (u, a) = *frame;
+        if a == 0 {
+            stack.pop();
+            time.post_label[u] = j;
+            j += 1;
+            continue;
+        }
+        frame.1 = next[a];
+        let w = ends[a];
+        if time.pre_label[w] == 0 {
+            time.pre_label[w] = k;
+            k += 1;
+            stack.push((w, first[w]));
+        }
+    }
+    time
+}
+
+/// 辞書順最小のトポロジカル順序
+/// 入次数0の頂点のうち番号が最小のものを常に先に取り出す(topological_sort_kahnと同じ順序)
+/// 閉路があるときは None
+/// n: 頂点数
+fn topological_sort_lex(edge: &EdgeList, graph: &DirectedGraph, n: usize) -> Option<Vec<usize>> {
+    topological_sort_kahn(edge, graph, n).ok()
+}
+
+/// 2つの辺リストが、辺 (tail, head) の多重集合として同じグラフを表すかどうか
+/// 辺を追加した順番(辺の番号)の違いは無視し、多重辺はその本数まで比べる
+/// n: 頂点数(両方のグラフで共通)
+fn same_graph(a: &EdgeList, b: &EdgeList, _n: usize) -> bool {
+    fn sorted_pairs(edge: &EdgeList) -> Vec<(usize, usize)> {
+        let mut pairs: Vec<(usize, usize)> = edge.tail[1..]
+            .iter()
+            .copied()
+            .zip(edge.head[1..].iter().copied())
+            .collect();
+        pairs.sort_unstable();
+        pairs
+    }
+    sorted_pairs(a) == sorted_pairs(b)
+}
+
+/// 辺aを縮約したグラフの辺リストを作る
+/// 辺aの両端のうち番号の大きい方の頂点を小さい方にまとめ、辺a自身は取り除く
+/// まとめた頂点より番号の大きい頂点は1つずつ詰めるので、縮約後の頂点は 1..=n-1 になる
+/// 残りの辺は元の順番のまま番号を詰める
+/// 両端を結んでいた他の辺は自己ループになり、drop_self_loops が true ならそれらも(元からある自己ループも)取り除く
+/// 返り値は新しい辺リストと辺数
+/// n: 頂点数
+/// m: 辺数
+/// a: 縮約する辺(自己ループでないこと)
+fn contract_edge(
+    edge: &EdgeList,
+    n: usize,
+    m: usize,
+    a: usize,
+    drop_self_loops: bool,
+) -> (EdgeList, usize) {
+    let (low, high) = if edge.tail[a] < edge.head[a] {
+        (edge.tail[a], edge.head[a])
+    } else {
+        (edge.head[a], edge.tail[a])
+    };
+    debug_assert!(low != high, "edge {a} is a self-loop");
+    debug_assert!(high <= n, "vertex {high} is out of range 1..={n}");
+    let rename = |v: usize| match v.cmp(&high) {
+        std::cmp::Ordering::Less => v,
+        std::cmp::Ordering::Equal => low,
+        std::cmp::Ordering::Greater => v - 1,
+    };
+
+    let mut result = EdgeList {
+        tail: vec![0],
+        head: vec![0],
+    };
+    for b in (1..=m).filter(|&b| b != a) {
+        let (u, w) = (rename(edge.tail[b]), rename(edge.head[b]));
+        if drop_self_loops && u == w {
+            continue;
+        }
+        result.tail.push(u);
+        result.head.push(w);
+    }
+    let count = result.tail.len() - 1;
+    (result, count)
+}
+
+/// Kargerの乱択アルゴリズムによる最小カット(辺の向きは無視する)
+/// 頂点が2つになるまでランダムに選んだ辺を縮約し(自己ループは取り除く)、
+/// 残った辺の本数をカットの大きさとする。これをiterations回繰り返した最小値を返す
+/// 1回の試行で最小カットが得られる確率は 2/(n(n-1)) 以上なので、iterationsは n^2 程度にする
+/// 乱数はrandom::XorShiftを使い、同じseedなら同じ結果になる
+/// 途中で辺がなくなったとき(連結でないとき)は0、iterations = 0 のときは usize::MAX
+/// n: 頂点数(2以上)
+/// m: 辺数
+fn min_cut_karger(edge: &EdgeList, n: usize, m: usize, iterations: usize, seed: u64) -> usize {
+    let mut rng = random::XorShift::new(seed);
+    let mut best = usize::MAX;
+    for _ in 0..iterations {
+        let (mut current, mut count) = remove_self_loops(edge, m);
+        let mut vertices = n;
+        while vertices > 2 && count > 0 {
+            let a = 1 + rng.below(count);
+            (current, count) = contract_edge(&current, vertices, count, a, true);
+            vertices -= 1;
+        }
+        best = best.min(if vertices > 2 { 0 } else { count });
+    }
+    best
+}
+
+/// 有向線グラフ(元のグラフの辺を頂点とするグラフ)
+/// 元の辺aの終点から辺bが出ている(head[a] == tail[b])とき、線グラフに辺 a -> b を加える
+/// 線グラフの頂点の番号は元の辺の番号そのままで、自己ループaからは a -> a ができる
+/// 辺はaの小さい順、同じaの中では接続リストの順に並べる
+/// 返り値は線グラフの辺リストと頂点数(= m)
+/// n: 頂点数
+/// m: 辺数
+fn line_graph(edge: &EdgeList, graph: &DirectedGraph, _n: usize, m: usize) -> (EdgeList, usize) {
+    let mut result = EdgeList {
+        tail: vec![0],
+        head: vec![0],
+    };
+    for a in 1..=m {
+        for b in out_edges(graph, edge.head[a]) {
+            result.tail.push(a);
+            result.head.push(b);
+        }
+    }
+    (result, m)
+}
+
+/// 全ての初等閉路(同じ頂点を2度通らない有向閉路)を列挙する(Johnsonのアルゴリズム)
+/// s = 1..=n の順に、番号がs以上の頂点だけからなる部分グラフでsを含む強連結成分を取り、
+/// sから始まってsに戻る閉路を、行き止まりになった頂点をブロックしながら探す
+/// 各閉路は最小の番号の頂点から始まる頂点の列で、自己ループは長さ1の閉路になる
+/// 頂点の列が同じになる閉路(多重辺によるもの)は1つとして数える
+/// 閉路の数は最悪で頂点数の指数になるので、max_cycles に Some(k) を渡すとk個見つけた時点で打ち切る
+/// 再帰を使わないので、長い閉路でもスタックはあふれない
+/// n: 頂点数
+fn simple_cycles(
+    edge: &EdgeList,
+    graph: &DirectedGraph,
+    n: usize,
+    max_cycles: Option<usize>,
+) -> Vec<Vec<usize>> {
+    /// blocked[u]を外し、uが外れるのを待っていた頂点も順に外す(非再帰)
+    fn unblock(u: usize, blocked: &mut [bool], waiting: &mut [Vec<usize>]) {
+        blocked[u] = false;
+        let mut stack = vec![u];
+        while let Some(x) = stack.pop() {
+            while let Some(w) = waiting[x].pop() {
+                if blocked[w] {
+                    blocked[w] = false;
+                    stack.push(w);
+                }
+            }
+        }
+    }
+
+    let cap = max_cycles.unwrap_or(usize::MAX);
+    let mut cycles = Vec::new();
+    let mut blocked = vec![false; n + 1];
+    let mut waiting = vec![Vec::new(); n + 1];
+    // backward[v] == s: 番号がs以上の頂点だけを通ってvからsへ届く
+    // component[v] == s: さらにsからvへも届く(vはsを含む強連結成分にある)
+    // sごとに配列を作り直さないように、印としてsを書き込む
+    let mut backward = vec![0; n + 1];
+    let mut component = vec![0; n + 1];
+    let mut path = Vec::new();
+    for s in 1..=n {
+        if cycles.len() >= cap {
+            break;
+        }
+        backward[s] = s;
+        let mut stack = vec![s];
+        while let Some(u) = stack.pop() {
+            for a in in_edges(graph, u) {
+                let w = edge.tail[a];
+                if w >= s && backward[w] != s {
+                    backward[w] = s;
+                    stack.push(w);
+                }
+            }
+        }
+        component[s] = s;
+        let mut members = vec![s];
+        let mut stack = vec![s];
+        while let Some(u) = stack.pop() {
+            for a in out_edges(graph, u) {
+                let w = edge.head[a];
+                if backward[w] == s && component[w] != s {
+                    component[w] = s;
+                    members.push(w);
+                    stack.push(w);
+                }
+            }
+        }
+        for &v in &members {
+            blocked[v] = false;
+            waiting[v].clear();
+        }
+
+        // 成分の中でvから出る辺の終点(重複なし)
+        let successors = |v: usize| {
+            let mut next: Vec<usize> = out_edges(graph, v)
+                .map(|a| edge.head[a])
+                .filter(|&w| component[w] == s)
+                .collect();
+            next.sort_unstable();
+            next.dedup();
+            next
+        };
+        // sからpathに沿って進む深さ優先探索(非再帰)
+        // フレームは (頂点v, vの次の頂点の列, 次に調べる位置, vから先でsに戻る閉路が見つかったか)
+        path.push(s);
+        blocked[s] = true;
+        let mut stack = vec![(s, successors(s), 0, false)];
+        while let Some(frame) = stack.last_mut() {
+            if frame.2 < frame.1.len() && cycles.len() < cap {
+                let w = frame.1[frame.2];
+                frame.2 += 1;
+                if w == s {
+                    cycles.push(path.clone());
+                    frame.3 = true;
+                } else if !blocked[w] {
+                    path.push(w);
+                    blocked[w] = true;
+                    stack.push((w, successors(w), 0, false));
+                }
+                continue;
+            }
+            let (v, next, _, found) = stack.pop().unwrap();
+            if found {
+                unblock(v, &mut blocked, &mut waiting);
+                if let Some(parent) = stack.last_mut() {
+                    parent.3 = true;
+                }
+            } else {
+                // vから先はまだsに戻れないので、隣の頂点のブロックが外れるまでvもブロックしておく
+                for &w in &next {
+                    if !waiting[w].contains(&v) {
+                        waiting[w].push(v);
+                    }
+                }
+            }
+            path.pop();
+        }
+    }
+    cycles
+}
+
+/// 辺 1..=m を番号の順に (始点, 終点) の組で返す
+/// 先頭のダミーは含まない
+/// m: 辺数
+fn edges(edge: &EdgeList, m: usize) -> impl Iterator<Item = (usize, usize)> + '_ {
+    (1..=m).map(move |a| (edge.tail[a], edge.head[a]))
+}
+
+/// sourceから到達できる頂点と辺だけを取り出した部分グラフ
+/// 残った頂点には元の番号の小さい順に1から番号を付け直す
+/// 到達できる頂点から出る辺は終点も到達できるので、そのような辺を元の順番のまま全て残す
+/// 返り値は新しい辺リストと頂点数、および original[新しい番号] = 元の番号 の対応(original[0]はダミーの0)
+/// n: 頂点数
+/// m: 辺数
+/// source: 始点
+fn reachable_subgraph(
+    edge: &EdgeList,
+    graph: &DirectedGraph,
+    n: usize,
+    m: usize,
+    source: usize,
+) -> (EdgeList, usize, Vec<usize>) {
+    let dist = bfs(edge, graph, n, source);
+    // renumber[v] 元の頂点vの新しい番号(到達できなければ0)
+    let mut renumber = vec![0; n + 1];
+    let mut original = vec![0];
+    for v in 1..=n {
+        if dist[v] != usize::MAX {
+            original.push(v);
+            renumber[v] = original.len() - 1;
+        }
+    }
+
+    let mut result = EdgeList {
+        tail: vec![0],
+        head: vec![0],
+    };
+    for a in (1..=m).filter(|&a| renumber[edge.tail[a]] != 0) {
+        result.tail.push(renumber[edge.tail[a]]);
+        result.head.push(renumber[edge.head[a]]);
+    }
+    (result, original.len() - 1, original)
+}
+
+/// 閉路を含むかもしれないグラフを、並行して処理できる頂点の層に分ける
+/// 強連結成分を縮約したグラフ(condensation)を世代分け(topological_generations)し、
+/// 各世代の成分に含まれる元の頂点を1つの層にまとめる
+/// 層の中の頂点は番号の小さい順に並べる
+/// n: 頂点数
+fn scc_dag_layers(edge: &EdgeList, graph: &DirectedGraph, n: usize) -> Vec<Vec<usize>> {
+    // condensationの頂点の番号はstrongly_connected_componentsの成分番号と同じ
+    let component = strongly_connected_components(edge, graph, n);
+    let (dag, count) = condensation(edge, graph, n);
+    let dag_graph = dicomp_incidence_list_construct(&dag, count, dag.tail.len() - 1);
+    let generations =
+        topological_generations(&dag, &dag_graph, count).expect("condensation is acyclic");
+
+    // layer_of[c] 成分cが属する層
+    let mut layer_of = vec![0; count + 1];
+    for (i, generation) in generations.iter().enumerate() {
+        for &c in generation {
+            layer_of[c] = i;
+        }
+    }
+    let mut layers = vec![Vec::new(); generations.len()];
+    for v in 1..=n {
+        layers[layer_of[component[v]]].push(v);
+    }
+    layers
+}
+
+/// 辺を1本ずつつないで辺リストを作るビルダー
+/// 先頭のダミーの0は自動で入れる
+/// 例: EdgeListBuilder::new().edge(1, 2).edge(1, 3).finish()
+struct EdgeListBuilder {
+    edge: EdgeList,
+}
+
+impl EdgeListBuilder {
+    /// 辺のない状態で作る
+    fn new() -> Self {
+        EdgeListBuilder {
+            edge: EdgeList {
+                tail: vec![0],
+                head: vec![0],
+            },
+        }
+    }
+
+    /// 辺 tail -> head を追加する。辺の番号は追加した順に1から付く
+    fn edge(mut self, tail: usize, head: usize) -> Self {
+        self.edge.tail.push(tail);
+        self.edge.head.push(head);
+        self
+    }
+
+    /// 辺リストと辺数mを返す
+    fn finish(self) -> (EdgeList, usize) {
+        let m = self.edge.tail.len() - 1;
+        (self.edge, m)
+    }
+}
+
+/// ワーシャル・フロイド法による全点対最短路
+/// weight_matrix[i][j] 頂点 i+1 から頂点 j+1 への辺の重み(0始まり、辺がなければ i64::MAX)
+/// dist[i][j] 頂点 i+1 から頂点 j+1 への最短路の長さ(到達できなければ i64::MAX)
+/// dist[i][i] は0から始めるので、対角成分が負になればその頂点を通る負閉路がある
+/// 負閉路があるときの他の成分は最短路の長さではない(桁あふれしないよう i64::MIN で止める)
+/// n: 頂点数
+fn floyd_warshall(weight_matrix: &[Vec<i64>], n: usize) -> Vec<Vec<i64>> {
+    let mut dist = weight_matrix[..n].to_vec();
+    for (i, row) in dist.iter_mut().enumerate() {
+        row[i] = row[i].min(0);
+    }
+    for k in 0..n {
+        for i in 0..n {
+            if dist[i][k] == i64::MAX {
+                continue;
+            }
+            for j in 0..n {
+                if dist[k][j] == i64::MAX {
+                    continue;
+                }
+                let candidate = dist[i][k].saturating_add(dist[k][j]);
+                if candidate < dist[i][j] {
+                    dist[i][j] = candidate;
+                }
+            }
+        }
+    }
+    dist
+}
+
+/// 辺リストと重みからワーシャル・フロイド法で全点対最短路を求める
+/// 多重辺は重みの最小の辺だけを使い、自己ループは対角成分に入れる
+/// 返り値はfloyd_warshallと同じ0始まりの行列
+/// weight[a] 辺aの重み(先頭にダミーの0を入れ、辺の番号と揃える)
+/// n: 頂点数
+/// m: 辺数
+fn floyd_warshall_edges(edge: &EdgeList, weight: &[i64], n: usize, m: usize) -> Vec<Vec<i64>> {
+    let mut matrix = vec![vec![i64::MAX; n]; n];
+    for a in 1..=m {
+        let entry = &mut matrix[edge.tail[a] - 1][edge.head[a] - 1];
+        *entry = (*entry).min(weight[a]);
+    }
+    floyd_warshall(&matrix, n)
+}
+
+/// トポロジカル順での各頂点の位置
+/// rank[v] topological_sortが返す順序で頂点vが何番目か(1から数える。rank[0]はダミーの0)
+/// 辺 u -> w があれば rank[u] < rank[w] なので、前後関係をO(1)で調べられる
+/// 閉路があるときは None
+/// n: 頂点数
+fn topological_rank(edge: &EdgeList, graph: &DirectedGraph, n: usize) -> Option<Vec<usize>> {
+    let order = topological_sort(edge, graph, n)?;
+    let mut rank = vec![0; n + 1];
+    for (i, &v) in order.iter().enumerate() {
+        rank[v] = i + 1;
+    }
+    Some(rank)
+}
+
+/// 頂点uとvが同じ強連結成分に属するかどうか
+/// 成分全体は求めず、uからvへ、vからuへの2回の探索(dfs_find)だけで判定する
+/// u == v なら常にtrue
+/// n: 頂点数
+fn same_scc(edge: &EdgeList, graph: &DirectedGraph, n: usize, u: usize, v: usize) -> bool {
+    dfs_find(edge, graph, n, u, v) && dfs_find(edge, graph, n, v, u)
+}
+
+/// 補グラフ(元のグラフにない順序対 (u, v), u != v を全て辺にしたグラフ)
+/// 元のグラフは単純グラフ(多重辺がない)であることを仮定する
+/// 自己ループは元のグラフにあってもなくても補グラフには含めない
+/// 辺の番号は (u, v) の小さい順に付ける
+/// 返り値は補グラフの辺リストと辺数
+/// n: 頂点数
+/// m: 辺数
+fn complement(edge: &EdgeList, n: usize, m: usize) -> (EdgeList, usize) {
+    let present: HashSet<(usize, usize)> = edges(edge, m).collect();
+    let mut result = EdgeList {
+        tail: vec![0],
+        head: vec![0],
+    };
+    for u in 1..=n {
+        for v in (1..=n).filter(|&v| v != u && !present.contains(&(u, v))) {
+            result.tail.push(u);
+            result.head.push(v);
+        }
+    }
+    let count = result.tail.len() - 1;
+    (result, count)
+}
+
+/// targetへ到達できる頂点を番号の小さい順に返す
+/// 逆向きの鎖(rev_edge_first, rev_edge_next)をtargetからたどる
+/// 長さ0の路も数えるので、target自身も常に含む
+/// n: 頂点数
+fn ancestors(edge: &EdgeList, graph: &DirectedGraph, n: usize, target: usize) -> Vec<usize> {
+    reachable_set(
+        n,
+        target,
+        &graph.rev_edge_first,
+        &graph.rev_edge_next,
+        &edge.tail,
+    )
+}
+
+/// startから鎖 first, next をたどって届く頂点(start自身を含む)を番号の小さい順に返す
+/// ends[a] は辺aをたどった先の頂点(順向きならhead、逆向きならtail)
+fn reachable_set(
+    n: usize,
+    start: usize,
+    first: &[usize],
+    next: &[usize],
+    ends: &[usize],
+) -> Vec<usize> {
+    let mut visited = vec![false; n + 1];
+    visited[start] = true;
+    let mut stack = vec![start];
+    while let Some(u) = stack.pop() {
+        let mut a = first[u];
+        while a != 0 {
+            let w = ends[a];
+            if !visited[w] {
+                visited[w] = true;
+                stack.push(w);
+            }
+            a = next[a];
+        }
+    }
+    (1..=n).filter(|&v| visited[v]).collect()
+}
+
+/// sourceから到達できる頂点を番号の小さい順に返す(ancestorsの逆向き)
+/// 鎖 edge_first, edge_next をsourceからたどる
+/// 長さ0の路も数えるので、source自身も常に含む(dfsで pre_label が0でない頂点と同じ集合)
+/// n: 頂点数
+fn descendants(edge: &EdgeList, graph: &DirectedGraph, n: usize, source: usize) -> Vec<usize> {
+    reachable_set(n, source, &graph.edge_first, &graph.edge_next, &edge.head)
+}
+
+/// 有向カットの大きさ(始点がset_aに属し、終点が属さない辺の本数)
+/// set_aの中の重複は無視する
+/// n: 頂点数
+/// m: 辺数
+/// set_a: 頂点の集合
+fn cut_size(edge: &EdgeList, n: usize, m: usize, set_a: &[usize]) -> usize {
+    let mut in_a = vec![false; n + 1];
+    for &v in set_a {
+        in_a[v] = true;
+    }
+    edges(edge, m).filter(|&(u, w)| in_a[u] && !in_a[w]).count()
+}
+
+/// 探索を始める順番を指定した深さ優先探索森
+/// start_orderの頂点を前から順に調べ、まだ訪問していない頂点から新しく探索を始める
+/// (dfs_forestは start_order = 1..=n の場合にあたる)
+/// Kosarajuの2回目の探索のように、後行順ラベルの大きい順に始めたいときに使う
+/// 返り値はラベルと、tree[v] 頂点vを含む木の番号(木を作った順に1から。訪問しなかった頂点は0)
+/// n: 頂点数
+/// start_order: 探索の始点の候補の列
+fn dfs_forest_ordered(
+    edge: &EdgeList,
+    graph: &DirectedGraph,
+    n: usize,
+    start_order: &[usize],
+) -> (DfsTime, Vec<usize>) {
+    let mut time = DfsTime {
+        pre_label: vec![0; n + 1],
+        post_label: vec![0; n + 1],
+    };
+
+    // 先行順のラベル
+    let mut k: usize = 1;
+    // 後行順のラベル
+    let mut j: usize = 1;
+
+    let mut is_root = vec![false; n + 1];
+    let mut stack = Vec::new();
+    for &v in start_order {
+        if time.pre_label[v] == 0 {
+            is_root[v] = true;
+            dfs_iterative_from(edge, graph, v, &mut time, &mut k, &mut j, &mut stack);
+        }
+    }
+
+    // 1つの木の頂点には先行順のラベルが続けて付くので、ラベルの順に根で区切る
+    let mut order_by_pre = vec![0; k];
+    for v in 1..=n {
+        order_by_pre[time.pre_label[v]] = v;
+    }
+    let mut tree = vec![0; n + 1];
+    let mut t = 0;
+    for &v in &order_by_pre[1..] {
+        if is_root[v] {
+            t += 1;
+        }
+        tree[v] = t;
+    }
+    (time, tree)
+}
+
+/// 深さ優先探索木の辺(未訪問の頂点を発見したときにたどった辺)の番号を発見した順に返す
+/// 探索の順番はdfs_iterativeと同じで、sourceから到達できる頂点の数より1本少ない
+/// n: 頂点数
+/// source: 探索の始点(1..=nであること)
+fn dfs_tree_edges(edge: &EdgeList, graph: &DirectedGraph, n: usize, source: usize) -> Vec<usize> {
+    let mut tree_edges = Vec::new();
+    let _ = dfs_visit(edge, graph, n, source, |event| {
+        if let Event::TreeEdge(a) = event {
+            tree_edges.push(a);
+        }
+        ControlFlow::Continue(())
+    });
+    tree_edges
+}
+
+/// 辺リストに現れる頂点の番号を 1..=n に詰め直す
+/// 番号が飛び飛びのグラフ(1, 5, 100 など)を、配列を無駄にせずに各アルゴリズムへ渡すために使う
+/// 新しい番号は元の番号の小さい順に付け、辺の順番は変えない
+/// 返り値は新しい辺リストと頂点数n、および original[新しい番号] = 元の番号 の対応(original[0]はダミーの0)
+/// m: 辺数
+fn compact(edge: &EdgeList, m: usize) -> (EdgeList, usize, Vec<usize>) {
+    let mut original: Vec<usize> = edge.tail[1..=m]
+        .iter()
+        .chain(&edge.head[1..=m])
+        .copied()
+        .collect();
+    original.sort_unstable();
+    original.dedup();
+    original.insert(0, 0);
+
+    // 元の番号は大きいことがあるので、配列ではなく二分探索で新しい番号を引く
+    let renumber = |v: usize| original.binary_search(&v).unwrap();
+    let result = EdgeList {
+        tail: edge.tail[..=m].iter().map(|&v| renumber(v)).collect(),
+        head: edge.head[..=m].iter().map(|&v| renumber(v)).collect(),
+    };
+    let n = original.len() - 1;
+    (result, n, original)
+}
+
+/// 辺の向きを無視したときに木であるかどうか
+/// 連結で辺がちょうど n-1 本なら、閉路(自己ループや多重辺によるものを含む)は作れないので木になる
+/// 頂点がなければfalse
+/// n: 頂点数
+/// m: 辺数
+fn is_tree(edge: &EdgeList, graph: &DirectedGraph, n: usize, m: usize) -> bool {
+    n > 0 && m == n - 1 && is_weakly_connected(edge, graph, n)
+}
+
+/// 辺の向きを無視したときに森(閉路のないグラフ、連結でなくてもよい)であるかどうか
+/// 森では辺を1本加えるごとに連結成分が1つ減るので、成分の数が n - m になる
+/// 自己ループや多重辺があればfalse
+/// n: 頂点数
+/// m: 辺数
+fn is_forest(edge: &EdgeList, _graph: &DirectedGraph, n: usize, m: usize) -> bool {
+    m <= n && count_components_union_find(edge, n, m) == n - m
+}
+
+/// 複数の始点からの幅優先探索
+/// 全ての始点を距離0としてキューに入れてから探索し、各頂点から最も近い始点までの辺数を返す
+/// 始点ごとにbfsを行ったときの距離の最小値と同じで、探索は1回で済む
+/// 到達できない頂点は usize::MAX とする。sourcesの中の重複は無視する
+/// n: 頂点数
+/// sources: 始点の列
+fn multi_source_bfs(
+    edge: &EdgeList,
+    graph: &DirectedGraph,
+    n: usize,
+    sources: &[usize],
+) -> Vec<usize> {
+    let mut dist = vec![usize::MAX; n + 1];
+    let mut queue = VecDeque::new();
+    for &s in sources {
+        if dist[s] != 0 {
+            dist[s] = 0;
+            queue.push_back(s);
+        }
+    }
+    while let Some(u) = queue.pop_front() {
+        for a in out_edges(graph, u) {
+            let w = edge.head[a];
+            if dist[w] == usize::MAX {
+                dist[w] = dist[u] + 1;
+                queue.push_back(w);
+            }
+        }
+    }
+    dist
+}
+
+/// 閉路があってトポロジカル順に並べきれなかったことを表すエラー
+/// remainingには一度も取り出せなかった頂点の数を入れる
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct CycleError {
+    remaining: usize,
+}
+
+impl fmt::Display for CycleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "cycle leaves {} vertices unordered", self.remaining)
+    }
+}
+
+impl std::error::Error for CycleError {}
+
+/// トポロジカル順に頂点を1つずつ返すイテレータ(Kahnのアルゴリズム)
+/// 順序全体を先に求めず、入次数が0になった頂点から順に取り出せる
+/// 閉路のせいで取り出せない頂点が残ったときは、最後に1度だけ Err(CycleError) を返して終わる
+struct TopoOrder<'a> {
+    edge: &'a EdgeList,
+    graph: &'a DirectedGraph,
+    in_degree: Vec<usize>,
+    heap: BinaryHeap<Reverse<usize>>,
+    // まだ取り出していない頂点の数
+    remaining: usize,
+    // CycleErrorを返し終えたか
+    done: bool,
+}
+
+impl Iterator for TopoOrder<'_> {
+    type Item = Result<usize, CycleError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(Reverse(u)) = self.heap.pop() {
+            self.remaining -= 1;
+            for a in out_edges(self.graph, u) {
+                let w = self.edge.head[a];
+                self.in_degree[w] -= 1;
+                if self.in_degree[w] == 0 {
+                    self.heap.push(Reverse(w));
+                }
+            }
+            return Some(Ok(u));
+        }
+        if self.remaining > 0 && !self.done {
+            self.done = true;
+            return Some(Err(CycleError {
+                remaining: self.remaining,
+            }));
+        }
+        None
+    }
+}
+
+/// トポロジカル順に頂点を返すイテレータを作る
+/// 順番はtopological_sort_kahnと同じ
+/// n: 頂点数
+fn topo_iter<'a>(edge: &'a EdgeList, graph: &'a DirectedGraph, n: usize) -> TopoOrder<'a> {
+    let (_, in_degree) = degrees(edge, graph, n);
+    let heap = (1..=n)
+        .filter(|&v| in_degree[v] == 0)
+        .map(Reverse)
+        .collect();
+    TopoOrder {
+        edge,
+        graph,
+        in_degree,
+        heap,
+        remaining: n,
+        done: false,
+    }
+}
+
+/// 重みが0か1の辺だけからなるグラフの単一始点最短路(0-1 BFS)
+/// 両端キューを使い、重み0の辺で更新した頂点は先頭に、重み1の辺で更新した頂点は末尾に入れる
+/// ヒープを使わないので、dijkstraより速い
+/// weight[a] 辺aの重み(0か1。先頭にダミーの0を入れ、辺の番号と揃える)
+/// dist[v] sourceから頂点vへの最短路の長さ(到達できない頂点は i64::MAX)
+/// n: 頂点数
+/// source: 始点
+fn zero_one_bfs(
+    edge: &EdgeList,
+    graph: &DirectedGraph,
+    weight: &[i64],
+    n: usize,
+    source: usize,
+) -> Vec<i64> {
+    let mut dist = vec![i64::MAX; n + 1];
+    let mut deque = VecDeque::new();
+    dist[source] = 0;
+    deque.push_back(source);
+    while let Some(u) = deque.pop_front() {
+        for a in out_edges(graph, u) {
+            debug_assert!(
+                weight[a] == 0 || weight[a] == 1,
+                "edge {a} has weight {} (expected 0 or 1)",
+                weight[a]
+            );
+            let w = edge.head[a];
+            let candidate = dist[u] + weight[a];
+            if candidate < dist[w] {
+                dist[w] = candidate;
+                if weight[a] == 0 {
+                    deque.push_front(w);
+                } else {
+                    deque.push_back(w);
+                }
+            }
+        }
+    }
+    dist
+}
+
+/// 重みが 0..=max_weight の小さい整数のグラフの単一始点最短路(Dialのアルゴリズム)
+/// 暫定距離ごとのバケツに頂点を入れ、距離の小さいバケツから順に取り出す
+/// 未確定の距離は常に (今の距離)..=(今の距離 + max_weight) にあるので、バケツは max_weight + 1 個を使い回す
+/// weight[a] 辺aの重み(先頭にダミーの0を入れ、辺の番号と揃える)
+/// dist[v] sourceから頂点vへの最短路の長さ(到達できない頂点は i64::MAX)
+/// n: 頂点数
+/// source: 始点
+/// max_weight: 重みの最大値
+fn dial_bfs(
+    edge: &EdgeList,
+    graph: &DirectedGraph,
+    weight: &[i64],
+    n: usize,
+    source: usize,
+    max_weight: usize,
+) -> Vec<i64> {
+    let mut dist = vec![i64::MAX; n + 1];
+    let size = max_weight + 1;
+    let mut buckets = vec![Vec::new(); size];
+    // バケツに入っている頂点の数(古くなったものも含む)
+    let mut pending = 1;
+    dist[source] = 0;
+    buckets[0].push(source);
+
+    let mut d = 0;
+    while pending > 0 {
+        let slot = d % size;
+        while let Some(u) = buckets[slot].pop() {
+            pending -= 1;
+            // より短い距離で取り出し済みなら古い要素なので読み飛ばす
+            if dist[u] != d as i64 {
+                continue;
+            }
+            for a in out_edges(graph, u) {
+                debug_assert!(
+                    (0..=max_weight as i64).contains(&weight[a]),
+                    "edge {a} has weight {} outside 0..={max_weight}",
+                    weight[a]
+                );
+                let w = edge.head[a];
+                let candidate = dist[u] + weight[a];
+                if candidate < dist[w] {
+                    dist[w] = candidate;
+                    buckets[candidate as usize % size].push(w);
+                    pending += 1;
+                }
+            }
+        }
+        d += 1;
+    }
+    dist
+}
+
+/// sourceから到達できる頂点の数(source自身を含む。descendantsの長さと同じ)
+/// 訪問済みの印と数だけを持つ深さ優先探索で数える
+/// n: 頂点数
+/// source: 始点
+fn reachable_count(edge: &EdgeList, graph: &DirectedGraph, n: usize, source: usize) -> usize {
+    let mut visited = vec![false; n + 1];
+    visited[source] = true;
+    let mut count = 1;
+    let mut stack = vec![source];
+    while let Some(u) = stack.pop() {
+        for a in out_edges(graph, u) {
+            let w = edge.head[a];
+            if !visited[w] {
+                visited[w] = true;
+                count += 1;
+                stack.push(w);
+            }
+        }
+    }
+    count
+}
+
+/// 媒介中心性(Brandesのアルゴリズム、重みなし)
+/// c[v] = 順序対 (s, t) (s != v != t) についての (sからtへの最短路のうちvを通るものの割合) の和
+/// 各頂点sから幅優先探索で最短路の数sigmaを数え、sから遠い頂点から順に依存度deltaを足し戻す
+///   delta[v] = 辺 v -> w で dist[w] = dist[v] + 1 となるwについての sigma[v] / sigma[w] * (1 + delta[w]) の和
+/// 有向グラフとして数えるので、無向グラフを両向きの辺で表したときは通常の(無向の)値の2倍になる
+/// 正規化はしない。c[0]はダミーの0
+/// n: 頂点数
+fn betweenness_centrality(edge: &EdgeList, graph: &DirectedGraph, n: usize) -> Vec<f64> {
+    let mut centrality = vec![0.0; n + 1];
+    for s in 1..=n {
+        let mut dist = vec![usize::MAX; n + 1];
+        // sigma[v] sからvへの最短路の数
+        let mut sigma = vec![0.0; n + 1];
+        let mut delta = vec![0.0; n + 1];
+        // 幅優先探索で取り出した順
+        let mut order = Vec::with_capacity(n);
+        let mut queue = VecDeque::new();
+        dist[s] = 0;
+        sigma[s] = 1.0;
+        queue.push_back(s);
+        while let Some(u) = queue.pop_front() {
+            order.push(u);
+            for a in out_edges(graph, u) {
+                let w = edge.head[a];
+                if dist[w] == usize::MAX {
+                    dist[w] = dist[u] + 1;
+                    queue.push_back(w);
+                }
+                if dist[w] == dist[u] + 1 {
+                    sigma[w] += sigma[u];
+                }
+            }
+        }
+
+        for &v in order.iter().rev() {
+            for a in out_edges(graph, v) {
+                let w = edge.head[a];
+                if dist[w] == dist[v] + 1 {
+                    delta[v] += sigma[v] / sigma[w] * (1.0 + delta[w]);
+                }
+            }
+            if v != s {
+                centrality[v] += delta[v];
+            }
+        }
+    }
+    centrality
+}
+
+/// 近接中心性(各頂点から到達できる頂点までの最短路の辺数の和の逆数)
+/// 各頂点から幅優先探索を行う
+/// 全ての頂点に到達できるとは限らないので、Wasserman–Faustの正規化を使う
+///   r = vから到達できるv以外の頂点の数, s = それらへの距離の和 として
+///   c[v] = (r / s) * (r / (n - 1))
+/// 全ての頂点に到達できるときは (n - 1) / s になる
+/// どこにも到達できない頂点は0。c[0]はダミーの0
+/// n: 頂点数
+fn closeness_centrality(edge: &EdgeList, graph: &DirectedGraph, n: usize) -> Vec<f64> {
+    let mut centrality = vec![0.0; n + 1];
+    for (v, c) in centrality.iter_mut().enumerate().skip(1) {
+        let dist = bfs(edge, graph, n, v);
+        let (mut r, mut s) = (0, 0);
+        for &d in &dist[1..] {
+            if d != usize::MAX && d > 0 {
+                r += 1;
+                s += d;
+            }
+        }
+        if r > 0 {
+            *c = (r as f64 / s as f64) * (r as f64 / (n - 1) as f64);
+        }
+    }
+    centrality
+}
+
+fn main() {
+    // 配列のindexを1から始めるため、先頭にダミーで0を入れておく
+    let graph = EdgeList {
+        tail: vec![0, 1, 1, 6, 6, 4, 5, 3, 2, 4],
+        head: vec![0, 2, 5, 2, 5, 1, 4, 6, 3, 3],
+    };
+    let directed_graph = dicomp_incidence_list_construct(&graph, 6, 9);
+
+    // 結果を表示
+    println!("edge_first: {:?}", directed_graph.edge_first);
+    println!("edge_next: {:?}", directed_graph.edge_next);
+    print!("{}", describe(&graph, &directed_graph, 6));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dicomp_incidence_list_construct() {
+        let graph = EdgeList {
+            tail: vec![0, 1, 1, 6, 6, 4, 5, 3, 2, 4],
+            head: vec![0, 2, 5, 2, 5, 1, 4, 6, 3, 3],
+        };
+        let directed_graph = dicomp_incidence_list_construct(&graph, 6, 9);
+
+        assert_eq!(directed_graph.edge_first, vec![0, 1, 8, 7, 5, 6, 3]);
+        assert_eq!(directed_graph.edge_next, vec![0, 2, 0, 4, 0, 9, 0, 0, 0, 0]);
+        assert_eq!(directed_graph.rev_edge_first, vec![0, 5, 1, 8, 6, 2, 7]);
+        assert_eq!(
+            directed_graph.rev_edge_next,
+            vec![0, 3, 4, 0, 0, 0, 0, 0, 9, 0]
+        );
+    }
+    #[test]
+    fn self_loop() {
+        let g = EdgeList {
+            tail: vec![0, 2],
+            head: vec![0, 2],
+        };
+        let dg = dicomp_incidence_list_construct(&g, 3, 1);
+        assert_eq!(dg.edge_first, vec![0, 0, 1, 0]);
+        assert_eq!(dg.edge_next, vec![0, 0]);
+        assert_eq!(dg.rev_edge_first, vec![0, 0, 1, 0]);
+        assert_eq!(dg.rev_edge_next, vec![0, 0]);
+    }
+
+    #[test]
+    fn multi_edges_same_pair() {
+        let g = EdgeList {
+            tail: vec![0, 1, 1],
+            head: vec![0, 2, 2],
+        };
+        let dg = dicomp_incidence_list_construct(&g, 2, 2);
+        assert_eq!(dg.edge_first, vec![0, 1, 0]);
+        assert_eq!(dg.edge_next, vec![0, 2, 0]);
+        assert_eq!(dg.rev_edge_first, vec![0, 0, 1]);
+        assert_eq!(dg.rev_edge_next, vec![0, 2, 0]);
+    }
+
+    #[test]
+    fn preserves_input_order_per_tail() {
+        // a=1..4 を (1→4),(1→3),(1→2),(1→5) とする
+        let g = EdgeList {
+            tail: vec![0, 1, 1, 1, 1],
+            head: vec![0, 4, 3, 2, 5],
+        };
+        let dg = dicomp_incidence_list_construct(&g, 5, 4);
+        assert_eq!(dg.edge_first[1], 1);
+        assert_eq!(dg.edge_next, vec![0, 2, 3, 4, 0]); // 1→2→3→4
+    }
+
+    #[test]
+    fn dfs_test() {
+        let graph = EdgeList {
+            tail: vec![0, 1, 1, 2, 6, 4, 5, 3, 2, 3],
+            head: vec![0, 2, 5, 6, 5, 1, 4, 6, 3, 4],
+        };
+        let directed_graph = dicomp_incidence_list_construct(&graph, 6, 9);
+        let result = dfs(&graph, &directed_graph, 6, 1);
+        assert_eq!(result.pre_label, vec![0, 1, 2, 6, 5, 4, 3]);
+        assert_eq!(result.post_label, vec![0, 6, 5, 4, 1, 2, 3]);
+    }
+    #[test]
+    fn dfs_linear() {
+        let graph = EdgeList {
+            tail: vec![0, 1, 2],
+            head: vec![0, 2, 3],
+        };
+        let directed_graph = dicomp_incidence_list_construct(&graph, 3, 2);
+        let result = dfs(&graph, &directed_graph, 3, 1);
+
+        // 訪問順は 1,2,3
+        assert_eq!(result.pre_label, vec![0, 1, 2, 3]);
+        // 帰りがけ順は逆順
+        assert_eq!(result.post_label, vec![0, 3, 2, 1]);
+    }
+    /// 分岐を持つ木構造: 1 -> 2, 1 -> 3
+    #[test]
+    fn dfs_branching() {
+        let graph = EdgeList {
+            tail: vec![0, 1, 1],
+            head: vec![0, 2, 3],
+        };
+        let directed_graph = dicomp_incidence_list_construct(&graph, 3, 2);
+        let result = dfs(&graph, &directed_graph, 3, 1);
+
+        // 先に 2, 次に 3 を探索すると仮定
+        assert_eq!(result.pre_label[1], 1);
+        assert!(result.pre_label[2] < result.pre_label[3]);
+        assert!(result.post_label[1] > result.post_label[2]);
+        assert!(result.post_label[1] > result.post_label[3]);
+    }
+
+    #[test]
+    fn dfs_cycle() {
+        let graph = EdgeList {
+            tail: vec![0, 1, 2, 3],
+            head: vec![0, 2, 3, 1],
+        };
+        let directed_graph = dicomp_incidence_list_construct(&graph, 3, 3);
+        let result = dfs(&graph, &directed_graph, 3, 1);
+
+        // すべて訪問できている（pre_labelが全て非ゼロ）
+        assert!(result.pre_label[1..=3].iter().all(|&x| x > 0));
+    }
+    #[test]
+    fn dfs_disconnected() {
+        let graph = EdgeList {
+            tail: vec![0, 1, 3],
+            head: vec![0, 2, 4],
+        };
+        let directed_graph = dicomp_incidence_list_construct(&graph, 4, 2);
+        let result = dfs(&graph, &directed_graph, 4, 1);
+        assert_eq!(result.pre_label, vec![0, 1, 2, 0, 0]);
+        assert_eq!(result.post_label, vec![0, 2, 1, 0, 0]);
+    }
+
+    #[test]
+    fn dfs_iterative_matches_recursive() {
+        let fixtures = [
+            (
+                EdgeList {
+                    tail: vec![0, 1, 1, 2, 6, 4, 5, 3, 2, 3],
+                    head: vec![0, 2, 5, 6, 5, 1, 4, 6, 3, 4],
+                },
+                6,
+                9,
+            ),
+            (
+                EdgeList {
+                    tail: vec![0, 1, 2],
+                    head: vec![0, 2, 3],
+                },
+                3,
+                2,
+            ),
+            (
+                EdgeList {
+                    tail: vec![0, 1, 1],
+                    head: vec![0, 2, 3],
+                },
+                3,
+                2,
+            ),
+            (
+                EdgeList {
+                    tail: vec![0, 1, 2, 3],
+                    head: vec![0, 2, 3, 1],
+                },
+                3,
+                3,
+            ),
+            (
+                EdgeList {
+                    tail: vec![0, 1, 3],
+                    head: vec![0, 2, 4],
+                },
+                4,
+                2,
+            ),
+        ];
+        for (graph, n, m) in &fixtures {
+            let directed_graph = dicomp_incidence_list_construct(graph, *n, *m);
+            let expected = dfs(graph, &directed_graph, *n, 1);
+            let result = dfs_iterative(graph, &directed_graph, *n, 1);
+            assert_eq!(result.pre_label, expected.pre_label);
+            assert_eq!(result.post_label, expected.post_label);
+        }
+    }
+
+    /// 再帰版ではスタックが溢れる長さのパス 1 -> 2 -> ... -> n
+    #[test]
+    fn dfs_iterative_deep_chain() {
+        let n = 1_000_000;
+        let mut tail = vec![0];
+        let mut head = vec![0];
+        for v in 1..n {
+            tail.push(v);
+            head.push(v + 1);
+        }
+        let graph = EdgeList { tail, head };
+        let directed_graph = dicomp_incidence_list_construct(&graph, n, n - 1);
+        let result = dfs_iterative(&graph, &directed_graph, n, 1);
+
+        assert_eq!(result.pre_label[1], 1);
+        assert_eq!(result.pre_label[n], n);
+        assert_eq!(result.post_label[n], 1);
+        assert_eq!(result.post_label[1], n);
+    }
+
+    #[test]
+    fn bfs_linear() {
+        let graph = EdgeList {
+            tail: vec![0, 1, 2],
+            head: vec![0, 2, 3],
+        };
+        let directed_graph = dicomp_incidence_list_construct(&graph, 3, 2);
+        let dist = bfs(&graph, &directed_graph, 3, 1);
+        assert_eq!(dist, vec![usize::MAX, 0, 1, 2]);
+    }
+
+    #[test]
+    fn bfs_disconnected() {
+        let graph = EdgeList {
+            tail: vec![0, 1, 3],
+            head: vec![0, 2, 4],
+        };
+        let directed_graph = dicomp_incidence_list_construct(&graph, 4, 2);
+        let dist = bfs(&graph, &directed_graph, 4, 1);
+        assert_eq!(dist, vec![usize::MAX, 0, 1, usize::MAX, usize::MAX]);
+    }
+
+    #[test]
+    fn bfs_shortest_level() {
+        // 1 -> 2 -> 3 -> 4 と近道 1 -> 4, 自己ループ 2 -> 2, 多重辺 1 -> 2
+        let graph = EdgeList {
+            tail: vec![0, 1, 2, 3, 1, 2, 1],
+            head: vec![0, 2, 3, 4, 4, 2, 2],
+        };
+        let directed_graph = dicomp_incidence_list_construct(&graph, 4, 6);
+        let dist = bfs(&graph, &directed_graph, 4, 1);
+        assert_eq!(dist, vec![usize::MAX, 0, 1, 2, 1]);
+    }
+
+    #[test]
+    fn dfs_forest_disconnected() {
+        let graph = EdgeList {
+            tail: vec![0, 1, 3],
+            head: vec![0, 2, 4],
+        };
+        let directed_graph = dicomp_incidence_list_construct(&graph, 4, 2);
+        let result = dfs_forest(&graph, &directed_graph, 4);
+
+        assert!(result.pre_label[1..=4].iter().all(|&x| x > 0));
+        assert!(result.post_label[1..=4].iter().all(|&x| x > 0));
+        // ラベルは木をまたいで通し番号になる
+        assert_eq!(result.pre_label, vec![0, 1, 2, 3, 4]);
+        assert_eq!(result.post_label, vec![0, 2, 1, 4, 3]);
+    }
+
+    #[test]
+    fn dfs_forest_matches_dfs_when_connected() {
+        let graph = EdgeList {
+            tail: vec![0, 1, 1, 2, 6, 4, 5, 3, 2, 3],
+            head: vec![0, 2, 5, 6, 5, 1, 4, 6, 3, 4],
+        };
+        let directed_graph = dicomp_incidence_list_construct(&graph, 6, 9);
+        let expected = dfs(&graph, &directed_graph, 6, 1);
+        let result = dfs_forest(&graph, &directed_graph, 6);
+        assert_eq!(result.pre_label, expected.pre_label);
+        assert_eq!(result.post_label, expected.post_label);
+    }
+
+    #[test]
+    fn topological_sort_linear() {
+        let graph = EdgeList {
+            tail: vec![0, 1, 2],
+            head: vec![0, 2, 3],
+        };
+        let directed_graph = dicomp_incidence_list_construct(&graph, 3, 2);
+        assert_eq!(
+            topological_sort(&graph, &directed_graph, 3),
+            Some(vec![1, 2, 3])
+        );
+    }
+
+    #[test]
+    fn topological_sort_cycle() {
+        let graph = EdgeList {
+            tail: vec![0, 1, 2, 3],
+            head: vec![0, 2, 3, 1],
+        };
+        let directed_graph = dicomp_incidence_list_construct(&graph, 3, 3);
+        assert_eq!(topological_sort(&graph, &directed_graph, 3), None);
+    }
+
+    #[test]
+    fn topological_sort_self_loop() {
+        let g = EdgeList {
+            tail: vec![0, 2],
+            head: vec![0, 2],
+        };
+        let dg = dicomp_incidence_list_construct(&g, 3, 1);
+        assert_eq!(topological_sort(&g, &dg, 3), None);
+    }
+
+    #[test]
+    fn topological_sort_respects_every_edge() {
+        // 1 -> 2, 1 -> 3, 3 -> 2, 4 -> 3, 2 -> 5
+        let graph = EdgeList {
+            tail: vec![0, 1, 1, 3, 4, 2],
+            head: vec![0, 2, 3, 2, 3, 5],
+        };
+        let directed_graph = dicomp_incidence_list_construct(&graph, 5, 5);
+        let order = topological_sort(&graph, &directed_graph, 5).unwrap();
+        assert_eq!(order.len(), 5);
+        let mut position = [0; 6];
+        for (i, &v) in order.iter().enumerate() {
+            position[v] = i;
+        }
+        for a in 1..=5 {
+            assert!(position[graph.tail[a]] < position[graph.head[a]]);
+        }
+    }
+
+    #[test]
+    fn scc_cycle() {
+        let graph = EdgeList {
+            tail: vec![0, 1, 2, 3],
+            head: vec![0, 2, 3, 1],
+        };
+        let directed_graph = dicomp_incidence_list_construct(&graph, 3, 3);
+        let component = strongly_connected_components(&graph, &directed_graph, 3);
+        assert_eq!(component, vec![0, 1, 1, 1]);
+    }
+
+    #[test]
+    fn scc_linear() {
+        let graph = EdgeList {
+            tail: vec![0, 1, 2],
+            head: vec![0, 2, 3],
+        };
+        let directed_graph = dicomp_incidence_list_construct(&graph, 3, 2);
+        let component = strongly_connected_components(&graph, &directed_graph, 3);
+        // 成分は位相順に番号が付く
+        assert_eq!(component, vec![0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn scc_main_fixture() {
+        let graph = EdgeList {
+            tail: vec![0, 1, 1, 6, 6, 4, 5, 3, 2, 4],
+            head: vec![0, 2, 5, 2, 5, 1, 4, 6, 3, 3],
+        };
+        let directed_graph = dicomp_incidence_list_construct(&graph, 6, 9);
+        let component = strongly_connected_components(&graph, &directed_graph, 6);
+        // 1 -> 5 -> 4 -> 1 と 1 -> 2 -> 3 -> 6 -> 5 で全体が1つの成分になる
+        assert!(component[1..].iter().all(|&c| c == 1));
+    }
+
+    #[test]
+    fn graph_matches_manual_construction() {
+        let mut g = Graph::new(6);
+        for (t, h) in [
+            (1, 2),
+            (1, 5),
+            (2, 6),
+            (6, 5),
+            (4, 1),
+            (5, 4),
+            (3, 6),
+            (2, 3),
+            (3, 4),
+        ] {
+            g.add_edge(t, h);
+        }
+        assert_eq!(g.edge_count(), 9);
+
+        let graph = EdgeList {
+            tail: vec![0, 1, 1, 2, 6, 4, 5, 3, 2, 3],
+            head: vec![0, 2, 5, 6, 5, 1, 4, 6, 3, 4],
+        };
+        let directed_graph = dicomp_incidence_list_construct(&graph, 6, 9);
+        assert_eq!(g.edge_list().tail, graph.tail);
+        assert_eq!(g.edge_list().head, graph.head);
+        assert_eq!(g.directed_graph().edge_first, directed_graph.edge_first);
+        assert_eq!(g.directed_graph().edge_next, directed_graph.edge_next);
+
+        let result = g.dfs(1);
+        assert_eq!(result.pre_label, vec![0, 1, 2, 6, 5, 4, 3]);
+        assert_eq!(result.post_label, vec![0, 6, 5, 4, 1, 2, 3]);
+    }
+
+    #[test]
+    fn graph_rebuilds_after_add_edge() {
+        let mut g = Graph::new(3);
+        assert_eq!(g.add_edge(1, 2), 1);
+        assert_eq!(g.bfs(1), vec![usize::MAX, 0, 1, usize::MAX]);
+
+        assert_eq!(g.add_edge(2, 3), 2);
+        assert_eq!(g.bfs(1), vec![usize::MAX, 0, 1, 2]);
+        assert_eq!(g.dfs(1).pre_label, vec![0, 1, 2, 3]);
+    }
+
+    #[test]
+    #[should_panic(expected = "out of range")]
+    fn graph_add_edge_out_of_range() {
+        let mut g = Graph::new(3);
+        g.add_edge(1, 4);
+    }
+
+    #[test]
+    #[should_panic(expected = "out of range")]
+    fn graph_add_edge_zero() {
+        let mut g = Graph::new(3);
+        g.add_edge(0, 1);
+    }
+
+    #[test]
+    fn try_construct_ok() {
+        let graph = EdgeList {
+            tail: vec![0, 1, 1, 6, 6, 4, 5, 3, 2, 4],
+            head: vec![0, 2, 5, 2, 5, 1, 4, 6, 3, 3],
+        };
+        let directed_graph = try_construct(&graph, 6, 9).unwrap();
+        assert_eq!(directed_graph.edge_first, vec![0, 1, 8, 7, 5, 6, 3]);
+    }
+
+    #[test]
+    fn try_construct_vertex_out_of_range() {
+        let graph = EdgeList {
+            tail: vec![0, 1, 2],
+            head: vec![0, 2, 4],
+        };
+        assert_eq!(
+            try_construct(&graph, 3, 2).err(),
+            Some(GraphError::VertexOutOfRange { edge: 2, vertex: 4 })
+        );
+
+        let graph = EdgeList {
+            tail: vec![0, 0],
+            head: vec![0, 1],
+        };
+        assert_eq!(
+            try_construct(&graph, 3, 1).err(),
+            Some(GraphError::VertexOutOfRange { edge: 1, vertex: 0 })
+        );
+    }
+
+    #[test]
+    fn try_construct_length_mismatch() {
+        let graph = EdgeList {
+            tail: vec![0, 1, 2],
+            head: vec![0, 2],
+        };
+        assert_eq!(
+            try_construct(&graph, 3, 2).err(),
+            Some(GraphError::LengthMismatch)
+        );
+        let graph = EdgeList {
+            tail: vec![0, 1],
+            head: vec![0, 2],
+        };
+        assert_eq!(
+            try_construct(&graph, 3, 2).err(),
+            Some(GraphError::LengthMismatch)
+        );
+    }
+
+    #[test]
+    fn out_edges_preserves_input_order() {
+        let g = EdgeList {
+            tail: vec![0, 1, 1, 1, 1],
+            head: vec![0, 4, 3, 2, 5],
+        };
+        let dg = dicomp_incidence_list_construct(&g, 5, 4);
+        assert_eq!(out_edges(&dg, 1).collect::<Vec<_>>(), vec![1, 2, 3, 4]);
+        assert_eq!(out_edges(&dg, 2).count(), 0);
+        assert_eq!(in_edges(&dg, 3).collect::<Vec<_>>(), vec![2]);
+    }
+
+    #[test]
+    fn in_edges_main_fixture() {
+        let graph = EdgeList {
+            tail: vec![0, 1, 1, 6, 6, 4, 5, 3, 2, 4],
+            head: vec![0, 2, 5, 2, 5, 1, 4, 6, 3, 3],
+        };
+        let dg = dicomp_incidence_list_construct(&graph, 6, 9);
+        assert_eq!(out_edges(&dg, 6).collect::<Vec<_>>(), vec![3, 4]);
+        assert_eq!(in_edges(&dg, 2).collect::<Vec<_>>(), vec![1, 3]);
+        assert_eq!(in_edges(&dg, 3).collect::<Vec<_>>(), vec![8, 9]);
+    }
+
+    #[test]
+    fn degrees_main_fixture() {
+        let graph = EdgeList {
+            tail: vec![0, 1, 1, 6, 6, 4, 5, 3, 2, 4],
+            head: vec![0, 2, 5, 2, 5, 1, 4, 6, 3, 3],
+        };
+        let dg = dicomp_incidence_list_construct(&graph, 6, 9);
+        let (out_degree, in_degree) = degrees(&graph, &dg, 6);
+        assert_eq!(out_degree, vec![0, 2, 1, 1, 2, 1, 2]);
+        assert_eq!(in_degree, vec![0, 1, 2, 2, 1, 2, 1]);
+    }
+
+    #[test]
+    fn degrees_self_loop() {
+        let g = EdgeList {
+            tail: vec![0, 2],
+            head: vec![0, 2],
+        };
+        let dg = dicomp_incidence_list_construct(&g, 3, 1);
+        let (out_degree, in_degree) = degrees(&g, &dg, 3);
+        assert_eq!(out_degree, vec![0, 0, 1, 0]);
+        assert_eq!(in_degree, vec![0, 0, 1, 0]);
+    }
+
+    #[test]
+    fn topological_sort_kahn_linear() {
+        let graph = EdgeList {
+            tail: vec![0, 1, 2],
+            head: vec![0, 2, 3],
+        };
+        let directed_graph = dicomp_incidence_list_construct(&graph, 3, 2);
+        assert_eq!(
+            topological_sort_kahn(&graph, &directed_graph, 3),
+            Ok(vec![1, 2, 3])
+        );
+    }
+
+    #[test]
+    fn topological_sort_kahn_smallest_id_first() {
+        // 1 -> 2 だけの3頂点。最初の入次数0の頂点は1, 3で、1を取り出すと2も入次数0になる
+        // キュー(先入れ先出し)なら 1, 3, 2 になるが、番号の小さい2を3より先に取り出す
+        let graph = EdgeList {
+            tail: vec![0, 1],
+            head: vec![0, 2],
+        };
+        let directed_graph = dicomp_incidence_list_construct(&graph, 3, 1);
+        assert_eq!(
+            topological_sort_kahn(&graph, &directed_graph, 3),
+            Ok(vec![1, 2, 3])
+        );
+        let order: Result<Vec<usize>, CycleError> = topo_iter(&graph, &directed_graph, 3).collect();
+        assert_eq!(order, Ok(vec![1, 2, 3]));
+    }
+
+    #[test]
+    fn topological_sort_kahn_cycle() {
+        let graph = EdgeList {
+            tail: vec![0, 1, 2, 3],
+            head: vec![0, 2, 3, 1],
+        };
+        let directed_graph = dicomp_incidence_list_construct(&graph, 3, 3);
+        assert_eq!(
+            topological_sort_kahn(&graph, &directed_graph, 3),
+            Err(vec![1, 2, 3])
+        );
+    }
+
+    #[test]
+    fn topological_sort_kahn_reports_blocked_vertices() {
+        // 4 -> 1 -> 2 -> 3 -> 1, 3 -> 5
+        // 5は閉路の先にあるので取り出されない
+        let graph = EdgeList {
+            tail: vec![0, 4, 1, 2, 3, 3],
+            head: vec![0, 1, 2, 3, 1, 5],
+        };
+        let directed_graph = dicomp_incidence_list_construct(&graph, 5, 5);
+        assert_eq!(
+            topological_sort_kahn(&graph, &directed_graph, 5),
+            Err(vec![1, 2, 3, 5])
+        );
+    }
+
+    #[test]
+    fn has_cycle_fixtures() {
+        let cycle = EdgeList {
+            tail: vec![0, 1, 2, 3],
+            head: vec![0, 2, 3, 1],
+        };
+        let dg = dicomp_incidence_list_construct(&cycle, 3, 3);
+        assert!(has_cycle(&cycle, &dg, 3));
+
+        let linear = EdgeList {
+            tail: vec![0, 1, 2],
+            head: vec![0, 2, 3],
+        };
+        let dg = dicomp_incidence_list_construct(&linear, 3, 2);
+        assert!(!has_cycle(&linear, &dg, 3));
+
+        let branching = EdgeList {
+            tail: vec![0, 1, 1],
+            head: vec![0, 2, 3],
+        };
+        let dg = dicomp_incidence_list_construct(&branching, 3, 2);
+        assert!(!has_cycle(&branching, &dg, 3));
+    }
+
+    #[test]
+    fn has_cycle_self_loop() {
+        let g = EdgeList {
+            tail: vec![0, 2],
+            head: vec![0, 2],
+        };
+        let dg = dicomp_incidence_list_construct(&g, 3, 1);
+        assert!(has_cycle(&g, &dg, 3));
+    }
+
+    #[test]
+    fn has_cycle_in_later_component() {
+        // 1 -> 2 と 3 -> 4 -> 3
+        let g = EdgeList {
+            tail: vec![0, 1, 3, 4],
+            head: vec![0, 2, 4, 3],
+        };
+        let dg = dicomp_incidence_list_construct(&g, 4, 3);
+        assert!(has_cycle(&g, &dg, 4));
+    }
+
+    #[test]
+    fn transpose_swaps_adjacency() {
+        let graph = EdgeList {
+            tail: vec![0, 1, 1, 6, 6, 4, 5, 3, 2, 4],
+            head: vec![0, 2, 5, 2, 5, 1, 4, 6, 3, 3],
+        };
+        let dg = dicomp_incidence_list_construct(&graph, 6, 9);
+        let rev = transpose(&graph, 9);
+        let rev_dg = dicomp_incidence_list_construct(&rev, 6, 9);
+        assert_eq!(rev_dg.edge_first, dg.rev_edge_first);
+        assert_eq!(rev_dg.edge_next, dg.rev_edge_next);
+        assert_eq!(rev_dg.rev_edge_first, dg.edge_first);
+        assert_eq!(rev_dg.rev_edge_next, dg.edge_next);
+    }
+
+    #[test]
+    fn transpose_round_trip() {
+        let graph = EdgeList {
+            tail: vec![0, 1, 1, 6, 6, 4, 5, 3, 2, 4],
+            head: vec![0, 2, 5, 2, 5, 1, 4, 6, 3, 3],
+        };
+        let twice = transpose(&transpose(&graph, 9), 9);
+        assert_eq!(twice.tail, graph.tail);
+        assert_eq!(twice.head, graph.head);
+    }
+
+    #[test]
+    fn dijkstra_weighted_dag() {
+        // 1 -(4)-> 2, 1 -(1)-> 3, 3 -(2)-> 2, 2 -(1)-> 4, 3 -(5)-> 4
+        let graph = EdgeList {
+            tail: vec![0, 1, 1, 3, 2, 3],
+            head: vec![0, 2, 3, 2, 4, 4],
+        };
+        let weight = vec![0, 4, 1, 2, 1, 5];
+        let dg = dicomp_incidence_list_construct(&graph, 5, 5);
+        let dist = dijkstra(&graph, &dg, &weight, 5, 1);
+        assert_eq!(dist, vec![i64::MAX, 0, 3, 1, 4, i64::MAX]);
+    }
+
+    #[test]
+    fn dijkstra_zero_weights_and_cycle() {
+        // 1 -(0)-> 2 -(0)-> 1, 2 -(7)-> 3
+        let graph = EdgeList {
+            tail: vec![0, 1, 2, 2],
+            head: vec![0, 2, 1, 3],
+        };
+        let weight = vec![0, 0, 0, 7];
+        let dg = dicomp_incidence_list_construct(&graph, 3, 3);
+        assert_eq!(
+            dijkstra(&graph, &dg, &weight, 3, 2),
+            vec![i64::MAX, 0, 0, 7]
+        );
+    }
+
+    #[test]
+    fn bellman_ford_negative_edge() {
+        // 1 -(4)-> 2, 1 -(5)-> 3, 3 -(-3)-> 2, 2 -(2)-> 4
+        let graph = EdgeList {
+            tail: vec![0, 1, 1, 3, 2],
+            head: vec![0, 2, 3, 2, 4],
+        };
+        let weight = vec![0, 4, 5, -3, 2];
+        let dg = dicomp_incidence_list_construct(&graph, 5, 4);
+        assert_eq!(
+            bellman_ford(&graph, &dg, &weight, 5, 1),
+            Ok(vec![i64::MAX, 0, 2, 5, 4, i64::MAX])
+        );
+    }
+
+    #[test]
+    fn bellman_ford_negative_cycle() {
+        // 1 -(1)-> 2 -(-2)-> 3 -(-1)-> 2, 3 -(1)-> 4
+        let graph = EdgeList {
+            tail: vec![0, 1, 2, 3, 3],
+            head: vec![0, 2, 3, 2, 4],
+        };
+        let weight = vec![0, 1, -2, -1, 1];
+        let dg = dicomp_incidence_list_construct(&graph, 4, 4);
+        let err = bellman_ford(&graph, &dg, &weight, 4, 1).unwrap_err();
+        let v = err.vertex.unwrap();
+        assert!(v == 2 || v == 3);
+    }
+
+    #[test]
+    fn bellman_ford_unreachable_negative_cycle() {
+        // 負閉路 2 <-> 3 は始点1から到達できない
+        let graph = EdgeList {
+            tail: vec![0, 2, 3],
+            head: vec![0, 3, 2],
+        };
+        let weight = vec![0, -1, -1];
+        let dg = dicomp_incidence_list_construct(&graph, 3, 2);
+        assert_eq!(
+            bellman_ford(&graph, &dg, &weight, 3, 1),
+            Ok(vec![i64::MAX, 0, i64::MAX, i64::MAX])
+        );
+    }
+
+    #[test]
+    fn to_dot_multi_edges() {
+        let g = EdgeList {
+            tail: vec![0, 1, 1],
+            head: vec![0, 2, 2],
+        };
+        assert_eq!(to_dot(&g, 2, 2), "digraph {\n    1 -> 2;\n    1 -> 2;\n}\n");
+    }
+
+    #[test]
+    fn to_dot_self_loop_and_isolated() {
+        let g = EdgeList {
+            tail: vec![0, 2],
+            head: vec![0, 2],
+        };
+        assert_eq!(
+            to_dot(&g, 3, 1),
+            "digraph {\n    1;\n    3;\n    2 -> 2;\n}\n"
+        );
+    }
+
+    #[test]
+    fn from_edge_text_main_fixture() {
+        let input =
+            "# 本文の例\n1 2\n1 5\n6 2\n6 5\n\n4 1\n5 4  # 末尾のコメント\n3 6\n2\t3\n4 3\n";
+        let (graph, m) = from_edge_text(input, 6).unwrap();
+        assert_eq!(m, 9);
+        assert_eq!(graph.tail, vec![0, 1, 1, 6, 6, 4, 5, 3, 2, 4]);
+        assert_eq!(graph.head, vec![0, 2, 5, 2, 5, 1, 4, 6, 3, 3]);
+        let dg = dicomp_incidence_list_construct(&graph, 6, m);
+        assert_eq!(dg.edge_first, vec![0, 1, 8, 7, 5, 6, 3]);
+    }
+
+    #[test]
+    fn from_edge_text_errors() {
+        assert_eq!(
+            from_edge_text("1 2\n1 x\n", 3).err(),
+            Some(ParseError::InvalidToken {
+                line: 2,
+                token: "x".to_string()
+            })
+        );
+        assert_eq!(
+            from_edge_text("1 2 3\n", 3).err(),
+            Some(ParseError::WrongTokenCount { line: 1 })
+        );
+        assert_eq!(
+            from_edge_text("# a\n1 4\n", 3).err(),
+            Some(ParseError::VertexOutOfRange { line: 2, vertex: 4 })
+        );
+        assert_eq!(
+            from_edge_text("0 1\n", 3).err(),
+            Some(ParseError::VertexOutOfRange { line: 1, vertex: 0 })
+        );
+    }
+
+    #[test]
+    fn connected_components_disconnected() {
+        let graph = EdgeList {
+            tail: vec![0, 1, 3],
+            head: vec![0, 2, 4],
+        };
+        let dg = dicomp_incidence_list_construct(&graph, 4, 2);
+        assert_eq!(connected_components(&graph, &dg, 4), vec![0, 1, 1, 2, 2]);
+    }
+
+    #[test]
+    fn connected_components_ignores_direction() {
+        // 2 -> 1, 3 -> 1 は向きを無視すれば1つにつながる。4は孤立点
+        let graph = EdgeList {
+            tail: vec![0, 2, 3],
+            head: vec![0, 1, 1],
+        };
+        let dg = dicomp_incidence_list_construct(&graph, 4, 2);
+        assert_eq!(connected_components(&graph, &dg, 4), vec![0, 1, 1, 1, 2]);
+    }
+
+    #[test]
+    fn is_bipartite_odd_cycle() {
+        let graph = EdgeList {
+            tail: vec![0, 1, 2, 3],
+            head: vec![0, 2, 3, 1],
+        };
+        let dg = dicomp_incidence_list_construct(&graph, 3, 3);
+        assert_eq!(is_bipartite(&graph, &dg, 3), None);
+    }
+
+    #[test]
+    fn is_bipartite_four_cycle() {
+        // 1 -> 2 -> 3 -> 4 -> 1 に加えて孤立点5
+        let graph = EdgeList {
+            tail: vec![0, 1, 2, 3, 4],
+            head: vec![0, 2, 3, 4, 1],
+        };
+        let dg = dicomp_incidence_list_construct(&graph, 5, 4);
+        assert_eq!(is_bipartite(&graph, &dg, 5), Some(vec![0, 1, 2, 1, 2, 1]));
+    }
+
+    #[test]
+    fn is_bipartite_self_loop() {
+        let g = EdgeList {
+            tail: vec![0, 2],
+            head: vec![0, 2],
+        };
+        let dg = dicomp_incidence_list_construct(&g, 3, 1);
+        assert_eq!(is_bipartite(&g, &dg, 3), None);
+    }
+
+    #[test]
+    fn articulation_points_two_triangles() {
+        // 三角形 1-2-3 と 3-4-5 が頂点3を共有する
+        let graph = EdgeList {
+            tail: vec![0, 1, 2, 3, 3, 4, 5],
+            head: vec![0, 2, 3, 1, 4, 5, 3],
+        };
+        let dg = dicomp_incidence_list_construct(&graph, 5, 6);
+        assert_eq!(articulation_points(&graph, &dg, 5), vec![3]);
+    }
+
+    #[test]
+    fn articulation_points_root_special_case() {
+        // 1が中心の星: 根1は子が3つあるので関節点
+        let graph = EdgeList {
+            tail: vec![0, 1, 1, 1],
+            head: vec![0, 2, 3, 4],
+        };
+        let dg = dicomp_incidence_list_construct(&graph, 4, 3);
+        assert_eq!(articulation_points(&graph, &dg, 4), vec![1]);
+
+        // パス 1 - 2 - 3: 根1は子が1つなので関節点ではない
+        let graph = EdgeList {
+            tail: vec![0, 1, 2],
+            head: vec![0, 2, 3],
+        };
+        let dg = dicomp_incidence_list_construct(&graph, 3, 2);
+        assert_eq!(articulation_points(&graph, &dg, 3), vec![2]);
+    }
+
+    #[test]
+    fn articulation_points_cycle() {
+        let graph = EdgeList {
+            tail: vec![0, 1, 2, 3],
+            head: vec![0, 2, 3, 1],
+        };
+        let dg = dicomp_incidence_list_construct(&graph, 3, 3);
+        assert!(articulation_points(&graph, &dg, 3).is_empty());
+    }
+
+    #[test]
+    fn bridges_path() {
+        let graph = EdgeList {
+            tail: vec![0, 1, 3, 3],
+            head: vec![0, 2, 2, 4],
+        };
+        let dg = dicomp_incidence_list_construct(&graph, 4, 3);
+        assert_eq!(bridges(&graph, &dg, 4), vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn bridges_cycle() {
+        let graph = EdgeList {
+            tail: vec![0, 1, 2, 3],
+            head: vec![0, 2, 3, 1],
+        };
+        let dg = dicomp_incidence_list_construct(&graph, 3, 3);
+        assert!(bridges(&graph, &dg, 3).is_empty());
+    }
+
+    #[test]
+    fn bridges_parallel_edges() {
+        let g = EdgeList {
+            tail: vec![0, 1, 1],
+            head: vec![0, 2, 2],
+        };
+        let dg = dicomp_incidence_list_construct(&g, 2, 2);
+        assert!(bridges(&g, &dg, 2).is_empty());
+
+        // 1 = 2 の多重辺に 2 - 3 をつなぐと 2 - 3 だけが橋
+        let g = EdgeList {
+            tail: vec![0, 1, 2, 2],
+            head: vec![0, 2, 1, 3],
+        };
+        let dg = dicomp_incidence_list_construct(&g, 3, 3);
+        assert_eq!(bridges(&g, &dg, 3), vec![3]);
+    }
+
+    #[test]
+    fn classify_edges_cycle() {
+        let graph = EdgeList {
+            tail: vec![0, 1, 2, 3],
+            head: vec![0, 2, 3, 1],
+        };
+        let dg = dicomp_incidence_list_construct(&graph, 3, 3);
+        let kind = classify_edges(&graph, &dg, 3);
+        assert_eq!(kind[1..], [EdgeKind::Tree, EdgeKind::Tree, EdgeKind::Back]);
+    }
+
+    #[test]
+    fn classify_edges_branching() {
+        let graph = EdgeList {
+            tail: vec![0, 1, 1],
+            head: vec![0, 2, 3],
+        };
+        let dg = dicomp_incidence_list_construct(&graph, 3, 2);
+        let kind = classify_edges(&graph, &dg, 3);
+        assert_eq!(kind[1..], [EdgeKind::Tree, EdgeKind::Tree]);
+    }
+
+    #[test]
+    fn classify_edges_all_kinds() {
+        // 1 -> 2 -> 3, 1 -> 3 (前進辺), 3 -> 1 (後退辺), 4 -> 2 (横断辺), 4 -> 4 (自己ループ)
+        let graph = EdgeList {
+            tail: vec![0, 1, 2, 1, 3, 4, 4],
+            head: vec![0, 2, 3, 3, 1, 2, 4],
+        };
+        let dg = dicomp_incidence_list_construct(&graph, 4, 6);
+        let kind = classify_edges(&graph, &dg, 4);
+        assert_eq!(
+            kind[1..],
+            [
+                EdgeKind::Tree,
+                EdgeKind::Tree,
+                EdgeKind::Forward,
+                EdgeKind::Back,
+                EdgeKind::Cross,
+                EdgeKind::Back,
+            ]
+        );
+    }
+
+    #[test]
+    fn graph_remove_edge_splices_chain() {
+        let mut g = Graph::new(4);
+        let a = g.add_edge(1, 2);
+        g.add_edge(1, 3);
+        g.add_edge(2, 4);
+        assert_eq!(g.bfs(1), vec![usize::MAX, 0, 1, 1, 2]);
+
+        assert!(g.remove_edge(a));
+        assert_eq!(
+            out_edges(g.directed_graph(), 1).collect::<Vec<_>>(),
+            vec![2]
+        );
+        assert_eq!(in_edges(g.directed_graph(), 2).count(), 0);
+        let result = g.dfs(1);
+        assert_eq!(result.pre_label, vec![0, 1, 0, 2, 0]);
+        assert_eq!(result.post_label, vec![0, 2, 0, 1, 0]);
+    }
+
+    #[test]
+    fn graph_remove_edge_before_build_and_after_add() {
+        let mut g = Graph::new(3);
+        g.add_edge(1, 2);
+        let b = g.add_edge(2, 3);
+        assert!(g.remove_edge(b));
+        assert_eq!(g.bfs(1), vec![usize::MAX, 0, 1, usize::MAX]);
+
+        // 辺を追加して作り直しても削除した辺は戻らない
+        g.add_edge(1, 3);
+        assert_eq!(g.bfs(1), vec![usize::MAX, 0, 1, 1]);
+        assert_eq!(g.bfs(2), vec![usize::MAX, usize::MAX, 0, usize::MAX]);
+    }
+
+    #[test]
+    fn graph_remove_edge_invalid() {
+        let mut g = Graph::new(2);
+        let a = g.add_edge(1, 2);
+        assert!(!g.remove_edge(0));
+        assert!(!g.remove_edge(2));
+        assert!(g.remove_edge(a));
+        assert!(!g.remove_edge(a));
+    }
+
+    #[test]
+    fn graph0_matches_one_indexed_dfs() {
+        let edges = [
+            (0, 1),
+            (0, 4),
+            (1, 5),
+            (5, 4),
+            (3, 0),
+            (4, 3),
+            (2, 5),
+            (1, 2),
+            (2, 3),
+        ];
+        let g = Graph0::from_edges(6, &edges);
+        let result = g.dfs(0);
+
+        let graph = EdgeList {
+            tail: vec![0, 1, 1, 2, 6, 4, 5, 3, 2, 3],
+            head: vec![0, 2, 5, 6, 5, 1, 4, 6, 3, 4],
+        };
+        let directed_graph = dicomp_incidence_list_construct(&graph, 6, 9);
+        let expected = dfs(&graph, &directed_graph, 6, 1);
+        assert_eq!(result.pre_label, expected.pre_label[1..]);
+        assert_eq!(result.post_label, expected.post_label[1..]);
+        assert_eq!(result.pre_label, vec![1, 2, 6, 5, 4, 3]);
+    }
+
+    #[test]
+    fn graph0_disconnected() {
+        let g = Graph0::from_edges(4, &[(0, 1), (2, 3)]);
+        let result = g.dfs(0);
+        assert_eq!(result.pre_label, vec![1, 2, 0, 0]);
+        assert_eq!(result.post_label, vec![2, 1, 0, 0]);
+        assert_eq!(g.bfs(2), vec![usize::MAX, usize::MAX, 0, 1]);
+    }
+
+    #[test]
+    fn reachability_linear() {
+        let graph = EdgeList {
+            tail: vec![0, 1, 2],
+            head: vec![0, 2, 3],
+        };
+        let dg = dicomp_incidence_list_construct(&graph, 3, 2);
+        let reach = reachability(&graph, &dg, 3);
+        assert_eq!(reach[1], vec![false, false, true, true]);
+        assert_eq!(reach[2], vec![false, false, false, true]);
+        assert_eq!(reach[3], vec![false; 4]);
+    }
+
+    #[test]
+    fn reachability_cycle() {
+        let graph = EdgeList {
+            tail: vec![0, 1, 2, 3],
+            head: vec![0, 2, 3, 1],
+        };
+        let dg = dicomp_incidence_list_construct(&graph, 3, 3);
+        let reach = reachability(&graph, &dg, 3);
+        for row in &reach[1..] {
+            assert!(row[1..].iter().all(|&r| r));
+        }
+    }
+
+    #[test]
+    fn reachability_self_loop() {
+        let g = EdgeList {
+            tail: vec![0, 2],
+            head: vec![0, 2],
+        };
+        let dg = dicomp_incidence_list_construct(&g, 3, 1);
+        let reach = reachability(&g, &dg, 3);
+        assert!(reach[2][2]);
+        assert!(!reach[1][1]);
+        assert!(!reach[3][3]);
+    }
+
+    #[test]
+    fn can_reach_linear() {
+        let graph = EdgeList {
+            tail: vec![0, 1, 2],
+            head: vec![0, 2, 3],
+        };
+        let dg = dicomp_incidence_list_construct(&graph, 3, 2);
+        assert!(can_reach(&graph, &dg, 3, 1, 3));
+        assert!(!can_reach(&graph, &dg, 3, 3, 1));
+        assert!(!can_reach(&graph, &dg, 3, 1, 1));
+    }
+
+    #[test]
+    fn can_reach_self() {
+        let g = EdgeList {
+            tail: vec![0, 2],
+            head: vec![0, 2],
+        };
+        let dg = dicomp_incidence_list_construct(&g, 3, 1);
+        assert!(can_reach(&g, &dg, 3, 2, 2));
+        assert!(!can_reach(&g, &dg, 3, 1, 1));
+
+        let cycle = EdgeList {
+            tail: vec![0, 1, 2, 3],
+            head: vec![0, 2, 3, 1],
+        };
+        let dg = dicomp_incidence_list_construct(&cycle, 3, 3);
+        assert!(can_reach(&cycle, &dg, 3, 1, 1));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip() {
+        let graph = EdgeList {
+            tail: vec![0, 1, 1, 6, 6, 4, 5, 3, 2, 4],
+            head: vec![0, 2, 5, 2, 5, 1, 4, 6, 3, 3],
+        };
+        let directed_graph = dicomp_incidence_list_construct(&graph, 6, 9);
+
+        let json = serde_json::to_string(&directed_graph).unwrap();
+        let restored: DirectedGraph = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.edge_first, directed_graph.edge_first);
+        assert_eq!(restored.edge_next, directed_graph.edge_next);
+        assert_eq!(restored.rev_edge_first, directed_graph.rev_edge_first);
+        assert_eq!(restored.rev_edge_next, directed_graph.rev_edge_next);
+
+        let json = serde_json::to_string(&graph).unwrap();
+        let restored: EdgeList = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.tail, graph.tail);
+        assert_eq!(restored.head, graph.head);
+
+        let time = dfs(&graph, &directed_graph, 6, 1);
+        let json = serde_json::to_string(&time).unwrap();
+        let restored: DfsTime = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.pre_label, time.pre_label);
+        assert_eq!(restored.post_label, time.post_label);
+    }
+
+    /// 2つの成分番号の配列が同じ分割を表しているか
+    fn same_partition(a: &[usize], b: &[usize]) -> bool {
+        let n = a.len() - 1;
+        (1..=n).all(|u| (1..=n).all(|v| (a[u] == a[v]) == (b[u] == b[v])))
+    }
+
+    #[test]
+    fn scc_tarjan_cycle() {
+        let graph = EdgeList {
+            tail: vec![0, 1, 2, 3],
+            head: vec![0, 2, 3, 1],
+        };
+        let dg = dicomp_incidence_list_construct(&graph, 3, 3);
+        let tarjan = scc_tarjan(&graph, &dg, 3);
+        assert_eq!(tarjan, vec![0, 1, 1, 1]);
+        assert!(same_partition(
+            &tarjan,
+            &strongly_connected_components(&graph, &dg, 3)
+        ));
+    }
+
+    #[test]
+    fn scc_tarjan_matches_kosaraju() {
+        // {1, 2, 3} -> {4, 5} -> 6, 7は孤立点
+        let graph = EdgeList {
+            tail: vec![0, 1, 2, 3, 3, 4, 5, 5],
+            head: vec![0, 2, 3, 1, 4, 5, 4, 6],
+        };
+        let dg = dicomp_incidence_list_construct(&graph, 7, 7);
+        let tarjan = scc_tarjan(&graph, &dg, 7);
+        assert!(same_partition(
+            &tarjan,
+            &strongly_connected_components(&graph, &dg, 7)
+        ));
+        // 確定した順: {6}, {4, 5}, {1, 2, 3}, {7}
+        assert_eq!(tarjan, vec![0, 3, 3, 3, 2, 2, 1, 4]);
+    }
+
+    #[test]
+    fn scc_tarjan_without_reverse_chains() {
+        let graph = EdgeList {
+            tail: vec![0, 1, 2, 3],
+            head: vec![0, 2, 3, 1],
+        };
+        let mut dg = dicomp_incidence_list_construct(&graph, 3, 3);
+        dg.rev_edge_first = vec![0; 4];
+        dg.rev_edge_next = vec![0; 4];
+        assert_eq!(scc_tarjan(&graph, &dg, 3), vec![0, 1, 1, 1]);
+    }
+
+    #[test]
+    fn condensation_cycle() {
+        let graph = EdgeList {
+            tail: vec![0, 1, 2, 3],
+            head: vec![0, 2, 3, 1],
+        };
+        let dg = dicomp_incidence_list_construct(&graph, 3, 3);
+        let (dag, count) = condensation(&graph, &dg, 3);
+        assert_eq!(count, 1);
+        assert_eq!(dag.tail, vec![0]);
+        assert_eq!(dag.head, vec![0]);
+    }
+
+    #[test]
+    fn condensation_is_acyclic() {
+        // {1, 2, 3} -> {4, 5} -> 6 で、{1, 2, 3} から {4, 5} への辺は2本ある
+        let graph = EdgeList {
+            tail: vec![0, 1, 2, 3, 3, 4, 5, 5, 2],
+            head: vec![0, 2, 3, 1, 4, 5, 4, 6, 5],
+        };
+        let dg = dicomp_incidence_list_construct(&graph, 6, 8);
+        let (dag, count) = condensation(&graph, &dg, 6);
+        assert_eq!(count, 3);
+        assert_eq!(dag.tail, vec![0, 1, 2]);
+        assert_eq!(dag.head, vec![0, 2, 3]);
+
+        let dag_graph = dicomp_incidence_list_construct(&dag, count, dag.tail.len() - 1);
+        assert_eq!(
+            topological_sort(&dag, &dag_graph, count),
+            Some(vec![1, 2, 3])
+        );
+    }
+
+    #[test]
+    fn count_paths_diamond() {
+        let graph = EdgeList {
+            tail: vec![0, 1, 1, 2, 3],
+            head: vec![0, 2, 3, 4, 4],
+        };
+        let dg = dicomp_incidence_list_construct(&graph, 4, 4);
+        assert_eq!(count_paths(&graph, &dg, 4, 1, 4), 2);
+        assert_eq!(count_paths(&graph, &dg, 4, 2, 4), 1);
+        assert_eq!(count_paths(&graph, &dg, 4, 4, 1), 0);
+        assert_eq!(count_paths(&graph, &dg, 4, 1, 1), 1);
+    }
+
+    #[test]
+    fn count_paths_multi_edges() {
+        let g = EdgeList {
+            tail: vec![0, 1, 1, 2],
+            head: vec![0, 2, 2, 3],
+        };
+        let dg = dicomp_incidence_list_construct(&g, 3, 3);
+        assert_eq!(count_paths(&g, &dg, 3, 1, 3), 2);
+    }
+
+    #[test]
+    fn count_paths_checked_cycle_and_overflow() {
+        let cycle = EdgeList {
+            tail: vec![0, 1, 2, 3],
+            head: vec![0, 2, 3, 1],
+        };
+        let dg = dicomp_incidence_list_construct(&cycle, 3, 3);
+        assert_eq!(count_paths_checked(&cycle, &dg, 3, 1, 3), None);
+
+        // 各段に2本の多重辺を持つ長さ64の路: 路の数は 2^64
+        let mut tail = vec![0];
+        let mut head = vec![0];
+        for v in 1..=64 {
+            for _ in 0..2 {
+                tail.push(v);
+                head.push(v + 1);
+            }
+        }
+        let g = EdgeList { tail, head };
+        let dg = dicomp_incidence_list_construct(&g, 65, 128);
+        assert_eq!(count_paths_checked(&g, &dg, 65, 2, 65), Some(1 << 63));
+        assert_eq!(count_paths_checked(&g, &dg, 65, 1, 65), None);
+    }
+
+    #[test]
+    fn dfs_with_capacity_matches_dfs() {
+        let graph = EdgeList {
+            tail: vec![0, 1, 1, 2, 6, 4, 5, 3, 2, 3],
+            head: vec![0, 2, 5, 6, 5, 1, 4, 6, 3, 4],
+        };
+        let directed_graph = dicomp_incidence_list_construct(&graph, 6, 9);
+        let expected = dfs(&graph, &directed_graph, 6, 1);
+        for stack_hint in [0, 1, 6, 100] {
+            let result = dfs_with_capacity(&graph, &directed_graph, 6, 1, stack_hint);
+            assert_eq!(result.pre_label, expected.pre_label);
+            assert_eq!(result.post_label, expected.post_label);
+        }
+    }
+
+    #[test]
+    fn dfs_with_capacity_deep_chain() {
+        let n = 100_000;
+        let mut tail = vec![0];
+        let mut head = vec![0];
+        for v in 1..n {
+            tail.push(v);
+            head.push(v + 1);
+        }
+        let graph = EdgeList { tail, head };
+        let directed_graph = dicomp_incidence_list_construct(&graph, n, n - 1);
+        let expected = dfs_iterative(&graph, &directed_graph, n, 1);
+        let result = dfs_with_capacity(&graph, &directed_graph, n, 1, n);
+        assert_eq!(result.pre_label, expected.pre_label);
+        assert_eq!(result.post_label, expected.post_label);
+    }
+
+    /// 路 1 -> 2 -> ... -> n に、頂点1から全ての頂点への近道 1 -> v を加えたグラフ
+    /// 路の辺を先に追加するので探索は路に沿って深さnまで進み、戻ってから近道を全て調べる
+    #[test]
+    fn dfs_with_capacity_chain_with_shortcuts() {
+        let n = 100_000;
+        let mut tail = vec![0];
+        let mut head = vec![0];
+        for v in 1..n {
+            tail.push(v);
+            head.push(v + 1);
+        }
+        for v in 2..=n {
+            tail.push(1);
+            head.push(v);
+        }
+        let m = tail.len() - 1;
+        let graph = EdgeList { tail, head };
+        let directed_graph = dicomp_incidence_list_construct(&graph, n, m);
+        let result = dfs_with_capacity(&graph, &directed_graph, n, 1, n);
+
+        // 訪問順は 1, 2, ..., n
+        assert!((1..=n).all(|v| result.pre_label[v] == v));
+        // 帰りがけ順は逆順
+        assert!((1..=n).all(|v| result.post_label[v] == n + 1 - v));
+    }
+
+    #[test]
+    fn post_order_matches_post_label() {
+        let graph = EdgeList {
+            tail: vec![0, 1, 1, 2, 6, 4, 5, 3, 2, 3],
+            head: vec![0, 2, 5, 6, 5, 1, 4, 6, 3, 4],
+        };
+        let directed_graph = dicomp_incidence_list_construct(&graph, 6, 9);
+        let time = dfs(&graph, &directed_graph, 6, 1);
+
+        let order: Vec<usize> = post_order(&graph, &directed_graph, 6, 1).collect();
+        assert_eq!(order, vec![4, 5, 6, 3, 2, 1]);
+        for (i, &v) in order.iter().enumerate() {
+            assert_eq!(time.post_label[v], i + 1);
+        }
+    }
+
+    #[test]
+    fn post_order_is_lazy() {
+        let graph = EdgeList {
+            tail: vec![0, 1, 3],
+            head: vec![0, 2, 4],
+        };
+        let directed_graph = dicomp_incidence_list_construct(&graph, 4, 2);
+        let mut iter = post_order(&graph, &directed_graph, 4, 1);
+        assert_eq!(iter.next(), Some(2));
+        assert_eq!(iter.next(), Some(1));
+        assert_eq!(iter.next(), None);
+    }
+
+    #[test]
+    fn find_cycle_triangle() {
+        let graph = EdgeList {
+            tail: vec![0, 1, 2, 3],
+            head: vec![0, 2, 3, 1],
+        };
+        let dg = dicomp_incidence_list_construct(&graph, 3, 3);
+        assert_eq!(find_cycle(&graph, &dg, 3), Some(vec![1, 2, 3]));
+    }
+
+    #[test]
+    fn find_cycle_self_loop_and_dag() {
+        let g = EdgeList {
+            tail: vec![0, 2],
+            head: vec![0, 2],
+        };
+        let dg = dicomp_incidence_list_construct(&g, 3, 1);
+        assert_eq!(find_cycle(&g, &dg, 3), Some(vec![2]));
+
+        let linear = EdgeList {
+            tail: vec![0, 1, 2],
+            head: vec![0, 2, 3],
+        };
+        let dg = dicomp_incidence_list_construct(&linear, 3, 2);
+        assert_eq!(find_cycle(&linear, &dg, 3), None);
+    }
+
+    #[test]
+    fn find_cycle_is_a_real_cycle() {
+        // 1 -> 2 -> 3 -> 4 -> 2 で、閉路の外の1は含まない
+        let graph = EdgeList {
+            tail: vec![0, 1, 2, 3, 4],
+            head: vec![0, 2, 3, 4, 2],
+        };
+        let dg = dicomp_incidence_list_construct(&graph, 4, 4);
+        let cycle = find_cycle(&graph, &dg, 4).unwrap();
+        assert_eq!(cycle, vec![2, 3, 4]);
+        let reach = reachability(&graph, &dg, 4);
+        for i in 0..cycle.len() {
+            let (u, w) = (cycle[i], cycle[(i + 1) % cycle.len()]);
+            assert!((1..=4).any(|a| graph.tail[a] == u && graph.head[a] == w));
+            assert!(reach[u][u]);
+        }
+    }
+
+    #[test]
+    fn from_adjacency_matrix_round_trip() {
+        let matrix = vec![
+            vec![false, true, true],
+            vec![false, true, false],
+            vec![true, false, false],
+        ];
+        let (edge, n, m) = from_adjacency_matrix(&matrix);
+        assert_eq!((n, m), (3, 4));
+        assert_eq!(edge.tail, vec![0, 1, 1, 2, 3]);
+        assert_eq!(edge.head, vec![0, 2, 3, 2, 1]);
+        let back: Vec<Vec<bool>> = to_adjacency_matrix(&edge, n, m)
+            .iter()
+            .map(|row| row.iter().map(|&x| x > 0).collect())
+            .collect();
+        assert_eq!(back, matrix);
+
+        let dg = dicomp_incidence_list_construct(&edge, n, m);
+        assert_eq!(bfs(&edge, &dg, n, 1), vec![usize::MAX, 0, 1, 1]);
+    }
+
+    #[test]
+    fn to_adjacency_matrix_multiplicity() {
+        let g = EdgeList {
+            tail: vec![0, 1, 1],
+            head: vec![0, 2, 2],
+        };
+        assert_eq!(to_adjacency_matrix(&g, 2, 2), vec![vec![0, 2], vec![0, 0]]);
+
+        let g = EdgeList {
+            tail: vec![0, 2],
+            head: vec![0, 2],
+        };
+        assert_eq!(
+            to_adjacency_matrix(&g, 3, 1),
+            vec![vec![0, 0, 0], vec![0, 1, 0], vec![0, 0, 0]]
+        );
+    }
+
+    #[test]
+    fn count_components_union_find_matches_dfs() {
+        let fixtures = [
+            (
+                EdgeList {
+                    tail: vec![0, 1, 3],
+                    head: vec![0, 2, 4],
+                },
+                4,
+                2,
+            ),
+            (
+                EdgeList {
+                    tail: vec![0, 2, 3],
+                    head: vec![0, 1, 1],
+                },
+                5,
+                2,
+            ),
+            (
+                EdgeList {
+                    tail: vec![0, 2],
+                    head: vec![0, 2],
+                },
+                3,
+                1,
+            ),
+            (
+                EdgeList {
+                    tail: vec![0, 1, 1, 6, 6, 4, 5, 3, 2, 4],
+                    head: vec![0, 2, 5, 2, 5, 1, 4, 6, 3, 3],
+                },
+                6,
+                9,
+            ),
+        ];
+        for (graph, n, m) in &fixtures {
+            let dg = dicomp_incidence_list_construct(graph, *n, *m);
+            let component = connected_components(graph, &dg, *n);
+            let expected = component.iter().copied().max().unwrap();
+            assert_eq!(count_components_union_find(graph, *n, *m), expected);
+        }
+    }
+
+    #[test]
+    fn count_components_union_find_isolated() {
+        let graph = EdgeList {
+            tail: vec![0],
+            head: vec![0],
+        };
+        assert_eq!(count_components_union_find(&graph, 4, 0), 4);
+    }
+
+    #[test]
+    fn euler_path_circuit() {
+        let graph = EdgeList {
+            tail: vec![0, 1, 2, 3],
+            head: vec![0, 2, 3, 1],
+        };
+        let dg = dicomp_incidence_list_construct(&graph, 3, 3);
+        assert_eq!(euler_path(&graph, &dg, 3, 3), Some(vec![1, 2, 3]));
+    }
+
+    #[test]
+    fn euler_path_linear() {
+        let graph = EdgeList {
+            tail: vec![0, 1, 2],
+            head: vec![0, 2, 3],
+        };
+        let dg = dicomp_incidence_list_construct(&graph, 3, 2);
+        assert_eq!(euler_path(&graph, &dg, 3, 2), Some(vec![1, 2]));
+    }
+
+    #[test]
+    fn euler_path_splices_sub_circuit() {
+        // 1 -> 2 -> 3 に 2 -> 4 -> 2 の閉路がぶら下がっている
+        let graph = EdgeList {
+            tail: vec![0, 1, 2, 2, 4],
+            head: vec![0, 2, 3, 4, 2],
+        };
+        let dg = dicomp_incidence_list_construct(&graph, 4, 4);
+        let path = euler_path(&graph, &dg, 4, 4).unwrap();
+        assert_eq!(path, vec![1, 3, 4, 2]);
+        for w in path.windows(2) {
+            assert_eq!(graph.head[w[0]], graph.tail[w[1]]);
+        }
+    }
+
+    #[test]
+    fn euler_path_none() {
+        // 分岐 1 -> 2, 1 -> 3 は出次数の条件を満たさない
+        let graph = EdgeList {
+            tail: vec![0, 1, 1],
+            head: vec![0, 2, 3],
+        };
+        let dg = dicomp_incidence_list_construct(&graph, 3, 2);
+        assert_eq!(euler_path(&graph, &dg, 3, 2), None);
+
+        // 次数の条件は満たすが2つの閉路がつながっていない
+        let graph = EdgeList {
+            tail: vec![0, 1, 2, 3, 4],
+            head: vec![0, 2, 1, 4, 3],
+        };
+        let dg = dicomp_incidence_list_construct(&graph, 4, 4);
+        assert_eq!(euler_path(&graph, &dg, 4, 4), None);
+    }
+
+    #[test]
+    fn parallel_edges_multi_edges() {
+        let g = EdgeList {
+            tail: vec![0, 1, 1],
+            head: vec![0, 2, 2],
+        };
+        assert_eq!(parallel_edges(&g, 2, 2), vec![(1, 2, vec![1, 2])]);
+    }
+
+    #[test]
+    fn parallel_edges_sorted_with_self_loops() {
+        // 3 -> 1 が2本, 2 -> 2 が2本, 1 -> 3 と 1 -> 2 は1本ずつ
+        let g = EdgeList {
+            tail: vec![0, 3, 2, 1, 3, 2, 1],
+            head: vec![0, 1, 2, 3, 1, 2, 2],
+        };
+        assert_eq!(
+            parallel_edges(&g, 3, 6),
+            vec![(2, 2, vec![2, 5]), (3, 1, vec![1, 4])]
+        );
+    }
+
+    #[test]
+    fn simplify_multi_edges() {
+        let g = EdgeList {
+            tail: vec![0, 1, 1],
+            head: vec![0, 2, 2],
+        };
+        let (simple, m) = simplify(&g, 2, 2, false);
+        assert_eq!(m, 1);
+        assert_eq!(simple.tail, vec![0, 1]);
+        assert_eq!(simple.head, vec![0, 2]);
+    }
+
+    #[test]
+    fn simplify_keeps_first_occurrence_order() {
+        // 2 -> 3, 1 -> 2, 2 -> 2, 2 -> 3, 1 -> 2, 2 -> 2
+        let g = EdgeList {
+            tail: vec![0, 2, 1, 2, 2, 1, 2],
+            head: vec![0, 3, 2, 2, 3, 2, 2],
+        };
+        let (simple, m) = simplify(&g, 3, 6, false);
+        assert_eq!(m, 3);
+        assert_eq!(simple.tail, vec![0, 2, 1, 2]);
+        assert_eq!(simple.head, vec![0, 3, 2, 2]);
+
+        let (simple, m) = simplify(&g, 3, 6, true);
+        assert_eq!(m, 2);
+        assert_eq!(simple.tail, vec![0, 2, 1]);
+        assert_eq!(simple.head, vec![0, 3, 2]);
+    }
+
+    #[test]
+    fn lca_small_tree() {
+        //         1
+        //       /   \
+        //      2     3
+        //     / \     \
+        //    4   5     6
+        //        |
+        //        7
+        let graph = EdgeList {
+            tail: vec![0, 1, 1, 2, 2, 3, 5],
+            head: vec![0, 2, 3, 4, 5, 6, 7],
+        };
+        let dg = dicomp_incidence_list_construct(&graph, 7, 6);
+        let table = lca_preprocess(&graph, &dg, 7, 1);
+        assert_eq!(table.up.len(), 3);
+        assert_eq!(table.query(4, 7), 2);
+        assert_eq!(table.query(7, 6), 1);
+        assert_eq!(table.query(5, 7), 5);
+        assert_eq!(table.query(4, 4), 4);
+        assert_eq!(table.query(1, 6), 1);
+    }
+
+    #[test]
+    fn lca_deep_chain_and_unreachable() {
+        // 1 -> 2 -> ... -> 9, 10 は孤立点
+        let mut tail = vec![0];
+        let mut head = vec![0];
+        for v in 1..9 {
+            tail.push(v);
+            head.push(v + 1);
+        }
+        let graph = EdgeList { tail, head };
+        let dg = dicomp_incidence_list_construct(&graph, 10, 8);
+        let table = lca_preprocess(&graph, &dg, 10, 1);
+        assert_eq!(table.query(9, 3), 3);
+        assert_eq!(table.query(2, 9), 2);
+        assert_eq!(table.query(9, 10), 0);
+    }
+
+    #[test]
+    fn bfs_paths_linear() {
+        let graph = EdgeList {
+            tail: vec![0, 1, 2],
+            head: vec![0, 2, 3],
+        };
+        let dg = dicomp_incidence_list_construct(&graph, 3, 2);
+        let (dist, pred) = bfs_paths(&graph, &dg, 3, 1);
+        assert_eq!(dist, bfs(&graph, &dg, 3, 1));
+        assert_eq!(pred, vec![0, 0, 1, 2]);
+        assert_eq!(reconstruct_path(&pred, 1, 3), Some(vec![1, 2, 3]));
+        assert_eq!(reconstruct_path(&pred, 1, 1), Some(vec![1]));
+    }
+
+    #[test]
+    fn bfs_paths_shortcut_and_unreachable() {
+        // 1 -> 2 -> 3 -> 4 と近道 1 -> 4, 5は孤立点
+        let graph = EdgeList {
+            tail: vec![0, 1, 2, 3, 1],
+            head: vec![0, 2, 3, 4, 4],
+        };
+        let dg = dicomp_incidence_list_construct(&graph, 5, 4);
+        let (_, pred) = bfs_paths(&graph, &dg, 5, 1);
+        assert_eq!(reconstruct_path(&pred, 1, 4), Some(vec![1, 4]));
+        assert_eq!(reconstruct_path(&pred, 1, 5), None);
+    }
+
+    #[test]
+    fn topological_generations_diamond() {
+        let graph = EdgeList {
+            tail: vec![0, 1, 1, 2, 3],
+            head: vec![0, 3, 2, 4, 4],
+        };
+        let dg = dicomp_incidence_list_construct(&graph, 4, 4);
+        assert_eq!(
+            topological_generations(&graph, &dg, 4),
+            Some(vec![vec![1], vec![2, 3], vec![4]])
+        );
+    }
+
+    #[test]
+    fn topological_generations_waits_for_all_dependencies() {
+        // 1 -> 2 -> 3, 1 -> 3, 4は孤立点
+        let graph = EdgeList {
+            tail: vec![0, 1, 2, 1],
+            head: vec![0, 2, 3, 3],
+        };
+        let dg = dicomp_incidence_list_construct(&graph, 4, 3);
+        assert_eq!(
+            topological_generations(&graph, &dg, 4),
+            Some(vec![vec![1, 4], vec![2], vec![3]])
+        );
+    }
+
+    #[test]
+    fn topological_generations_cycle() {
+        let graph = EdgeList {
+            tail: vec![0, 1, 2, 3],
+            head: vec![0, 2, 3, 1],
+        };
+        let dg = dicomp_incidence_list_construct(&graph, 3, 3);
+        assert_eq!(topological_generations(&graph, &dg, 3), None);
+    }
+
+    #[test]
+    fn describe_branching() {
+        let graph = EdgeList {
+            tail: vec![0, 1, 1],
+            head: vec![0, 2, 3],
+        };
+        let dg = dicomp_incidence_list_construct(&graph, 3, 2);
+        assert_eq!(describe(&graph, &dg, 3), "1 -> [2, 3]\n2 -> []\n3 -> []\n");
+    }
+
+    #[test]
+    fn describe_main_fixture() {
+        let graph = EdgeList {
+            tail: vec![0, 1, 1, 6, 6, 4, 5, 3, 2, 4],
+            head: vec![0, 2, 5, 2, 5, 1, 4, 6, 3, 3],
+        };
+        let dg = dicomp_incidence_list_construct(&graph, 6, 9);
+        assert_eq!(
+            describe(&graph, &dg, 6),
+            "1 -> [2, 5]\n2 -> [3]\n3 -> [6]\n4 -> [1, 3]\n5 -> [4]\n6 -> [2, 5]\n"
+        );
+    }
+
+    #[test]
+    fn construct_empty_graph() {
+        let g = EdgeList {
+            tail: vec![0],
+            head: vec![0],
+        };
+        let dg = dicomp_incidence_list_construct(&g, 0, 0);
+        assert_eq!(dg.edge_first, vec![0]);
+        assert_eq!(dg.edge_next, vec![0]);
+        assert_eq!(dg.rev_edge_first, vec![0]);
+        assert_eq!(dg.rev_edge_next, vec![0]);
+        assert_eq!(dfs_forest(&g, &dg, 0).pre_label, vec![0]);
+        assert_eq!(topological_sort(&g, &dg, 0), Some(Vec::new()));
+    }
+
+    #[test]
+    fn construct_no_edges() {
+        let g = EdgeList {
+            tail: vec![0],
+            head: vec![0],
+        };
+        let dg = dicomp_incidence_list_construct(&g, 3, 0);
+        assert_eq!(dg.edge_first, vec![0; 4]);
+        assert_eq!(dg.edge_next, vec![0]);
+        assert_eq!(dg.rev_edge_first, vec![0; 4]);
+        assert_eq!(dg.rev_edge_next, vec![0]);
+
+        let result = dfs(&g, &dg, 3, 2);
+        assert_eq!(result.pre_label, vec![0, 0, 1, 0]);
+        assert_eq!(result.post_label, vec![0, 0, 1, 0]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn dfs_start_out_of_range() {
+        let g = EdgeList {
+            tail: vec![0],
+            head: vec![0],
+        };
+        let dg = dicomp_incidence_list_construct(&g, 0, 0);
+        dfs(&g, &dg, 0, 1);
+    }
+
+    #[test]
+    fn dfs_depth_limited_branching() {
+        // 1 -> 2, 1 -> 3 に 2 -> 4 を加える
+        let graph = EdgeList {
+            tail: vec![0, 1, 1, 2],
+            head: vec![0, 2, 3, 4],
+        };
+        let dg = dicomp_incidence_list_construct(&graph, 4, 3);
+        assert_eq!(
+            dfs_depth_limited(&graph, &dg, 4, 1, 1),
+            vec![usize::MAX, 0, 1, 1, usize::MAX]
+        );
+        assert_eq!(
+            dfs_depth_limited(&graph, &dg, 4, 1, 0),
+            vec![usize::MAX, 0, usize::MAX, usize::MAX, usize::MAX]
+        );
+    }
+
+    #[test]
+    fn dfs_depth_limited_keeps_shallowest() {
+        // 1 -> 2 -> 5 -> 6 と遠回りの 1 -> 3 -> 4 -> 5
+        // スタックの都合で先に 5 を深さ3で見つけても、後から深さ2に更新され6まで届く
+        let graph = EdgeList {
+            tail: vec![0, 1, 1, 3, 4, 2, 5],
+            head: vec![0, 2, 3, 4, 5, 5, 6],
+        };
+        let dg = dicomp_incidence_list_construct(&graph, 6, 6);
+        assert_eq!(
+            dfs_depth_limited(&graph, &dg, 6, 1, 3),
+            vec![usize::MAX, 0, 1, 1, 2, 2, 3]
+        );
+    }
+
+    #[test]
+    fn is_weakly_connected_fixtures() {
+        let cycle = EdgeList {
+            tail: vec![0, 1, 2, 3],
+            head: vec![0, 2, 3, 1],
+        };
+        let dg = dicomp_incidence_list_construct(&cycle, 3, 3);
+        assert!(is_weakly_connected(&cycle, &dg, 3));
+
+        let disconnected = EdgeList {
+            tail: vec![0, 1, 3],
+            head: vec![0, 2, 4],
+        };
+        let dg = dicomp_incidence_list_construct(&disconnected, 4, 2);
+        assert!(!is_weakly_connected(&disconnected, &dg, 4));
+    }
+
+    #[test]
+    fn is_weakly_connected_ignores_direction_and_isolated() {
+        // 2 -> 1, 3 -> 1 は向きを無視すればつながる
+        let graph = EdgeList {
+            tail: vec![0, 2, 3],
+            head: vec![0, 1, 1],
+        };
+        let dg = dicomp_incidence_list_construct(&graph, 3, 2);
+        assert!(is_weakly_connected(&graph, &dg, 3));
+
+        // 孤立点4を加えるとつながらない
+        let dg = dicomp_incidence_list_construct(&graph, 4, 2);
+        assert!(!is_weakly_connected(&graph, &dg, 4));
+    }
+
+    #[test]
+    fn is_strongly_connected_fixtures() {
+        let cycle = EdgeList {
+            tail: vec![0, 1, 2, 3],
+            head: vec![0, 2, 3, 1],
+        };
+        let dg = dicomp_incidence_list_construct(&cycle, 3, 3);
+        assert!(is_strongly_connected(&cycle, &dg, 3));
+
+        let linear = EdgeList {
+            tail: vec![0, 1, 2],
+            head: vec![0, 2, 3],
+        };
+        let dg = dicomp_incidence_list_construct(&linear, 3, 2);
+        assert!(!is_strongly_connected(&linear, &dg, 3));
+    }
+
+    #[test]
+    fn is_strongly_connected_needs_reverse_pass() {
+        // 1から全てに届くが、3から1へは戻れない: 1 -> 2 -> 1, 1 -> 3
+        let graph = EdgeList {
+            tail: vec![0, 1, 2, 1],
+            head: vec![0, 2, 1, 3],
+        };
+        let dg = dicomp_incidence_list_construct(&graph, 3, 3);
+        assert!(!is_strongly_connected(&graph, &dg, 3));
+
+        let graph = EdgeList {
+            tail: vec![0, 1, 1, 6, 6, 4, 5, 3, 2, 4],
+            head: vec![0, 2, 5, 2, 5, 1, 4, 6, 3, 3],
+        };
+        let dg = dicomp_incidence_list_construct(&graph, 6, 9);
+        assert!(is_strongly_connected(&graph, &dg, 6));
+    }
+
+    #[test]
+    fn edge_list_data_labels() {
+        let mut labeled = EdgeListData::new("");
+        for (t, h, label) in [
+            (1, 2, "a"),
+            (1, 5, "b"),
+            (2, 6, "c"),
+            (6, 5, "d"),
+            (4, 1, "e"),
+            (5, 4, "f"),
+            (3, 6, "g"),
+            (2, 3, "h"),
+            (3, 4, "i"),
+        ] {
+            labeled.push(t, h, label);
+        }
+        let directed_graph = dicomp_incidence_list_construct(&labeled, 6, 9);
+        let result = dfs(&labeled, &directed_graph, 6, 1);
+        assert_eq!(result.pre_label, vec![0, 1, 2, 6, 5, 4, 3]);
+        assert_eq!(result.post_label, vec![0, 6, 5, 4, 1, 2, 3]);
+
+        let labels: Vec<&str> = out_edges(&directed_graph, 3)
+            .map(|a| *labeled.data(a))
+            .collect();
+        assert_eq!(labels, vec!["g", "i"]);
+    }
+
+    #[test]
+    fn counts_from_structures() {
+        let graph = EdgeList {
+            tail: vec![0, 1, 1, 6, 6, 4, 5, 3, 2, 4],
+            head: vec![0, 2, 5, 2, 5, 1, 4, 6, 3, 3],
+        };
+        let dg = dicomp_incidence_list_construct(&graph, 6, 9);
+        assert_eq!(vertex_count(&dg), 6);
+        assert_eq!(edge_count(&graph), 9);
+        assert_eq!(
+            validate(&graph, vertex_count(&dg), edge_count(&graph)),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn validate_errors() {
+        let graph = EdgeList {
+            tail: vec![1, 1],
+            head: vec![0, 2],
+        };
+        assert_eq!(validate(&graph, 2, 1), Err(GraphError::DummyNotZero));
+        assert_eq!(validate(&graph, 2, 2), Err(GraphError::LengthMismatch));
+
+        let graph = EdgeList {
+            tail: vec![0, 1],
+            head: vec![0, 3],
+        };
+        assert_eq!(
+            validate(&graph, 2, 1),
+            Err(GraphError::VertexOutOfRange { edge: 1, vertex: 3 })
+        );
+    }
+
+    #[test]
+    fn kruskal_small_graph() {
+        // 1 -(1)- 2, 2 -(2)- 3, 1 -(3)- 3, 3 -(4)- 4, 2 -(5)- 4, 4 -(1)- 4 (自己ループ)
+        let graph = EdgeList {
+            tail: vec![0, 1, 2, 1, 4, 2, 4],
+            head: vec![0, 2, 3, 3, 3, 4, 4],
+        };
+        let weight = vec![0, 1, 2, 3, 4, 5, 1];
+        let (total, chosen) = kruskal(&graph, &weight, 4, 6);
+        assert_eq!(total, 7);
+        assert_eq!(chosen, vec![1, 2, 4]);
+    }
+
+    #[test]
+    fn kruskal_ties_and_forest() {
+        // 三角形 1-2-3 の重みが全て同じで、4-5 は別の成分
+        let graph = EdgeList {
+            tail: vec![0, 2, 1, 1, 4],
+            head: vec![0, 3, 3, 2, 5],
+        };
+        let weight = vec![0, 1, 1, 1, -2];
+        let (total, chosen) = kruskal(&graph, &weight, 5, 4);
+        assert_eq!(total, 0);
+        assert_eq!(chosen, vec![4, 1, 2]);
+    }
+
+    #[test]
+    fn prim_matches_kruskal() {
+        let graph = EdgeList {
+            tail: vec![0, 1, 2, 1, 4, 2, 4],
+            head: vec![0, 2, 3, 3, 3, 4, 4],
+        };
+        let weight = vec![0, 1, 2, 3, 4, 5, 1];
+        let dg = dicomp_incidence_list_construct(&graph, 4, 6);
+        let (total, mut chosen) = prim(&graph, &dg, &weight, 4, 1);
+        assert_eq!(total, kruskal(&graph, &weight, 4, 6).0);
+        chosen.sort_unstable();
+        assert_eq!(chosen, vec![1, 2, 4]);
+
+        // 向きを無視するので、どの頂点から始めても同じ重みになる
+        for source in 2..=4 {
+            assert_eq!(prim(&graph, &dg, &weight, 4, source).0, 7);
+        }
+    }
+
+    #[test]
+    fn prim_random_graphs_match_kruskal() {
+        for seed in 1..=5 {
+            let (graph, m) = random::gnp(12, 0.4, seed, false);
+            let mut rng = random::XorShift::new(seed + 100);
+            let mut weight = vec![0];
+            weight.extend((1..=m).map(|_| rng.below(20) as i64));
+            let dg = dicomp_incidence_list_construct(&graph, 12, m);
+            if !is_weakly_connected(&graph, &dg, 12) {
+                continue;
+            }
+            let kruskal_total = kruskal(&graph, &weight, 12, m).0;
+            assert_eq!(prim(&graph, &dg, &weight, 12, 1).0, kruskal_total);
+        }
+    }
+
+    /// マッチングとして正しいか(与えた辺だけを使い、どの頂点も2回使わない)
+    fn is_valid_matching(pairs: &[(usize, usize)], matching: &[(usize, usize)]) -> bool {
+        let mut left = HashSet::new();
+        let mut right = HashSet::new();
+        matching
+            .iter()
+            .all(|p| pairs.contains(p) && left.insert(p.0) && right.insert(p.1))
+    }
+
+    #[test]
+    fn bipartite_matching_perfect() {
+        // 貪欲に (1, 1) を選ぶと行き詰まるが、完全マッチングが存在する
+        let pairs = [(1, 1), (1, 2), (2, 1), (3, 2), (3, 3)];
+        let matching = bipartite_matching(&pairs, 3, 3);
+        assert_eq!(matching.len(), 3);
+        assert!(is_valid_matching(&pairs, &matching));
+    }
+
+    #[test]
+    fn bipartite_matching_long_augmenting_path() {
+        // 左iは右i+1, 右iの順につながる。最初の段階で左iが右i+1を取るので、
+        // 左kから右1への長さ 2k - 1 の増加路が1本だけ残る
+        let k = 200_000;
+        let mut pairs = Vec::new();
+        for i in 1..=k {
+            if i < k {
+                pairs.push((i, i + 1));
+            }
+            pairs.push((i, i));
+        }
+        let matching = bipartite_matching(&pairs, k, k);
+        assert_eq!(matching, (1..=k).map(|i| (i, i)).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn bipartite_matching_not_perfect() {
+        // 左の1, 2, 3が全て右の1にしかつながらない
+        let pairs = [(1, 1), (2, 1), (3, 1), (3, 2)];
+        let matching = bipartite_matching(&pairs, 3, 2);
+        assert_eq!(matching.len(), 2);
+        assert!(is_valid_matching(&pairs, &matching));
+        assert!(bipartite_matching(&[], 2, 2).is_empty());
+    }
+
+    #[test]
+    fn greedy_color_cycles() {
+        let square = EdgeList {
+            tail: vec![0, 1, 2, 3, 4],
+            head: vec![0, 2, 3, 4, 1],
+        };
+        let dg = dicomp_incidence_list_construct(&square, 4, 4);
+        let color = greedy_color(&square, &dg, 4, &[1, 2, 3, 4]);
+        assert_eq!(color, vec![0, 1, 2, 1, 2]);
+        assert_eq!(
+            *greedy_color_by_degree(&square, &dg, 4)
+                .iter()
+                .max()
+                .unwrap(),
+            2
+        );
+
+        let triangle = EdgeList {
+            tail: vec![0, 1, 2, 3],
+            head: vec![0, 2, 3, 1],
+        };
+        let dg = dicomp_incidence_list_construct(&triangle, 3, 3);
+        assert_eq!(
+            greedy_color(&triangle, &dg, 3, &[1, 2, 3]),
+            vec![0, 1, 2, 3]
+        );
+        assert_eq!(greedy_color_by_degree(&triangle, &dg, 3), vec![0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn greedy_color_order_matters() {
+        // パス 1 - 3 - 4 - 2 は、1, 2 を先に塗ると3色になる
+        let graph = EdgeList {
+            tail: vec![0, 1, 3, 4],
+            head: vec![0, 3, 4, 2],
+        };
+        let dg = dicomp_incidence_list_construct(&graph, 4, 3);
+        assert_eq!(
+            greedy_color(&graph, &dg, 4, &[1, 2, 3, 4]),
+            vec![0, 1, 1, 2, 3]
+        );
+        let color = greedy_color_by_degree(&graph, &dg, 4);
+        assert_eq!(*color.iter().max().unwrap(), 2);
+        for a in 1..=3 {
+            assert_ne!(color[graph.tail[a]], color[graph.head[a]]);
+        }
+    }
+
+    #[test]
+    fn pagerank_sums_to_one() {
+        // 4は出る辺のない頂点
+        let graph = EdgeList {
+            tail: vec![0, 1, 1, 2, 3, 3],
+            head: vec![0, 2, 3, 3, 1, 4],
+        };
+        let dg = dicomp_incidence_list_construct(&graph, 4, 5);
+        let rank = pagerank(&graph, &dg, 4, 5, 0.85, 100);
+        assert_eq!(rank.len(), 5);
+        assert_eq!(rank[0], 0.0);
+        assert!((rank[1..].iter().sum::<f64>() - 1.0).abs() < 1e-9);
+        // 3は1と2の両方から指されているので最も高い
+        assert!(rank[3] > rank[1] && rank[3] > rank[2] && rank[3] > rank[4]);
+    }
+
+    #[test]
+    fn pagerank_converges_on_cycle() {
+        let graph = EdgeList {
+            tail: vec![0, 1, 2, 3],
+            head: vec![0, 2, 3, 1],
+        };
+        let dg = dicomp_incidence_list_construct(&graph, 3, 3);
+        let rank = pagerank(&graph, &dg, 3, 3, 0.85, 50);
+        for &r in &rank[1..] {
+            assert!((r - 1.0 / 3.0).abs() < 1e-9);
+        }
+
+        let before = pagerank(&graph, &dg, 3, 3, 0.85, 200);
+        let after = pagerank(&graph, &dg, 3, 3, 0.85, 201);
+        for v in 1..=3 {
+            assert!((before[v] - after[v]).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn k_core_clique() {
+        // 4頂点の完全グラフ(各組に1本ずつ)
+        let graph = EdgeList {
+            tail: vec![0, 1, 1, 1, 2, 2, 3],
+            head: vec![0, 2, 3, 4, 3, 4, 4],
+        };
+        let dg = dicomp_incidence_list_construct(&graph, 4, 6);
+        assert_eq!(k_core(&graph, &dg, 4), vec![0, 3, 3, 3, 3]);
+    }
+
+    #[test]
+    fn k_core_clique_with_tail() {
+        // 三角形 1-2-3 に 3 - 4 - 5 のひげ、6は孤立点、5に自己ループ
+        let graph = EdgeList {
+            tail: vec![0, 1, 2, 3, 3, 4, 5],
+            head: vec![0, 2, 3, 1, 4, 5, 5],
+        };
+        let dg = dicomp_incidence_list_construct(&graph, 6, 6);
+        assert_eq!(k_core(&graph, &dg, 6), vec![0, 2, 2, 2, 1, 1, 0]);
+    }
+
+    #[test]
+    fn bfs_tree_linear() {
+        let graph = EdgeList {
+            tail: vec![0, 1, 2],
+            head: vec![0, 2, 3],
+        };
+        let dg = dicomp_incidence_list_construct(&graph, 3, 2);
+        let (tree, m) = bfs_tree(&graph, &dg, 3, 1);
+        assert_eq!(m, 2);
+        assert_eq!(tree.tail, graph.tail);
+        assert_eq!(tree.head, graph.head);
+    }
+
+    #[test]
+    fn bfs_tree_prefers_shortcut() {
+        // 1 -> 2 -> 3 と近道 1 -> 3, 4は到達できない
+        let graph = EdgeList {
+            tail: vec![0, 1, 2, 1, 4],
+            head: vec![0, 2, 3, 3, 1],
+        };
+        let dg = dicomp_incidence_list_construct(&graph, 4, 4);
+        let (tree, m) = bfs_tree(&graph, &dg, 4, 1);
+        assert_eq!(m, 2);
+        assert_eq!(tree.tail, vec![0, 1, 1]);
+        assert_eq!(tree.head, vec![0, 2, 3]);
+    }
+
+    #[test]
+    fn self_loops_fixture() {
+        let g = EdgeList {
+            tail: vec![0, 2],
+            head: vec![0, 2],
+        };
+        assert_eq!(self_loops(&g, 1), vec![1]);
+        assert_eq!(count_self_loops(&g, 1), 1);
+        let (cleaned, m) = remove_self_loops(&g, 1);
+        assert_eq!(m, 0);
+        assert_eq!(cleaned.tail, vec![0]);
+    }
+
+    #[test]
+    fn remove_self_loops_keeps_order() {
+        let g = EdgeList {
+            tail: vec![0, 1, 2, 2, 3, 3],
+            head: vec![0, 2, 2, 3, 3, 1],
+        };
+        assert_eq!(self_loops(&g, 5), vec![2, 4]);
+        assert_eq!(count_self_loops(&g, 5), 2);
+        let (cleaned, m) = remove_self_loops(&g, 5);
+        assert_eq!(m, 3);
+        assert_eq!(cleaned.tail, vec![0, 1, 2, 3]);
+        assert_eq!(cleaned.head, vec![0, 2, 3, 1]);
+        assert_eq!(count_self_loops(&cleaned, m), 0);
+    }
+
+    #[test]
+    fn longest_path_dag_diamond() {
+        // 1 -> 2 -> 4, 1 -> 3 -> 4
+        let g = EdgeList {
+            tail: vec![0, 1, 1, 2, 3],
+            head: vec![0, 2, 3, 4, 4],
+        };
+        let graph = dicomp_incidence_list_construct(&g, 4, 4);
+        let (length, path) = longest_path_dag(&g, &graph, &[0, 1, 1, 1, 1], 4).unwrap();
+        assert_eq!(length, 2);
+        assert!(path == vec![1, 2, 4] || path == vec![1, 3, 4]);
+
+        let (length, path) = longest_path_dag(&g, &graph, &[0, 1, 5, 1, 2], 4).unwrap();
+        assert_eq!(length, 7);
+        assert_eq!(path, vec![1, 3, 4]);
+    }
+
+    #[test]
+    fn longest_path_dag_rejects_cycle() {
+        let g = EdgeList {
+            tail: vec![0, 1, 2, 3],
+            head: vec![0, 2, 3, 1],
+        };
+        let graph = dicomp_incidence_list_construct(&g, 3, 3);
+        assert_eq!(longest_path_dag(&g, &graph, &[0, 1, 1, 1], 3), None);
+    }
+
+    #[test]
+    fn incremental_forest_dfs_cycle() {
+        let g = EdgeList {
+            tail: vec![0, 1, 2, 3],
+            head: vec![0, 2, 3, 1],
+        };
+        let mut forest = IncrementalForest::new(3);
+        assert_eq!(forest.component_count(), 3);
+        let mut counts = Vec::new();
+        for a in 1..=3 {
+            forest.add_edge(g.tail[a], g.head[a]);
+            counts.push(forest.component_count());
+        }
+        // 3本目の辺 3 -> 1 は既に同じ成分の頂点どうしを結ぶ
+        assert_eq!(counts, vec![2, 1, 1]);
+        assert!(!forest.add_edge(1, 3));
+    }
+
+    #[test]
+    fn sources_and_sinks_linear() {
+        let graph = EdgeList {
+            tail: vec![0, 1, 2],
+            head: vec![0, 2, 3],
+        };
+        let directed_graph = dicomp_incidence_list_construct(&graph, 3, 2);
+        assert_eq!(sources(&graph, &directed_graph, 3), vec![1]);
+        assert_eq!(sinks(&graph, &directed_graph, 3), vec![3]);
+    }
+
+    #[test]
+    fn sources_and_sinks_self_loop() {
+        // 頂点2の自己ループだけ。1と3は孤立点なので湧き出し口かつ吸い込み口
+        let graph = EdgeList {
+            tail: vec![0, 2],
+            head: vec![0, 2],
+        };
+        let directed_graph = dicomp_incidence_list_construct(&graph, 3, 1);
+        assert_eq!(sources(&graph, &directed_graph, 3), vec![1, 3]);
+        assert_eq!(sinks(&graph, &directed_graph, 3), vec![1, 3]);
+    }
+
+    #[test]
+    fn dfs_with_parent_branching() {
+        let graph = EdgeList {
+            tail: vec![0, 1, 1],
+            head: vec![0, 2, 3],
+        };
+        let directed_graph = dicomp_incidence_list_construct(&graph, 3, 2);
+        let (time, parent) = dfs_with_parent(&graph, &directed_graph, 3, 1);
+        assert_eq!(parent, vec![0, 0, 1, 1]);
+        let expected = dfs_iterative(&graph, &directed_graph, 3, 1);
+        assert_eq!(time.pre_label, expected.pre_label);
+        assert_eq!(time.post_label, expected.post_label);
+    }
+
+    #[test]
+    fn dfs_with_parent_follows_discovery_edge() {
+        // 1 -> 2 -> 3 と 1 -> 3。辺1が先に調べられるので3は2から発見される
+        let graph = EdgeList {
+            tail: vec![0, 1, 2, 1],
+            head: vec![0, 2, 3, 3],
+        };
+        let directed_graph = dicomp_incidence_list_construct(&graph, 4, 3);
+        let (time, parent) = dfs_with_parent(&graph, &directed_graph, 4, 1);
+        assert_eq!(parent[2], 1);
+        assert_eq!(parent[3], 2);
+        assert_eq!(parent[1], 0);
+        assert_eq!(parent[4], 0);
+        assert_eq!(time.pre_label[4], 0);
+    }
+
+    #[test]
+    fn add_edge_to_graph_updates_dfs() {
+        let mut graph = EdgeList {
+            tail: vec![0, 1, 2],
+            head: vec![0, 2, 3],
+        };
+        let mut directed_graph = dicomp_incidence_list_construct(&graph, 4, 2);
+        assert_eq!(dfs(&graph, &directed_graph, 4, 1).pre_label[4], 0);
+
+        let a = add_edge_to_graph(&mut directed_graph, &mut graph, 3, 4);
+        assert_eq!(a, 3);
+        assert_eq!(in_edges(&directed_graph, 4).collect::<Vec<_>>(), vec![3]);
+        let result = dfs(&graph, &directed_graph, 4, 1);
+        assert_eq!(result.pre_label, vec![0, 1, 2, 3, 4]);
+
+        // 先頭につなぐので、後から追加した辺が先にたどられる
+        add_edge_to_graph(&mut directed_graph, &mut graph, 1, 4);
+        assert_eq!(
+            out_edges(&directed_graph, 1).collect::<Vec<_>>(),
+            vec![4, 1]
+        );
+        assert_eq!(validate(&graph, 4, 4), Ok(()));
+    }
+
+    #[test]
+    fn diameter_linear() {
+        let graph = EdgeList {
+            tail: vec![0, 1, 2],
+            head: vec![0, 2, 3],
+        };
+        let directed_graph = dicomp_incidence_list_construct(&graph, 3, 2);
+        assert_eq!(eccentricities(&graph, &directed_graph, 3), vec![0, 2, 1, 0]);
+        assert_eq!(diameter(&graph, &directed_graph, 3), 2);
+    }
+
+    #[test]
+    fn diameter_cycle() {
+        let graph = EdgeList {
+            tail: vec![0, 1, 2, 3],
+            head: vec![0, 2, 3, 1],
+        };
+        let directed_graph = dicomp_incidence_list_construct(&graph, 3, 3);
+        assert_eq!(eccentricities(&graph, &directed_graph, 3), vec![0, 2, 2, 2]);
+        assert_eq!(diameter(&graph, &directed_graph, 3), 2);
+    }
+
+    #[test]
+    fn neighbors_branching() {
+        let graph = EdgeList {
+            tail: vec![0, 1, 1],
+            head: vec![0, 2, 3],
+        };
+        let directed_graph = dicomp_incidence_list_construct(&graph, 3, 2);
+        assert_eq!(neighbors(&graph, &directed_graph, 1), vec![2, 3]);
+        assert!(neighbors(&graph, &directed_graph, 2).is_empty());
+        assert_eq!(predecessors(&graph, &directed_graph, 3), vec![1]);
+        assert!(predecessors(&graph, &directed_graph, 1).is_empty());
+    }
+
+    #[test]
+    fn neighbors_multi_edges() {
+        let graph = EdgeList {
+            tail: vec![0, 1, 1],
+            head: vec![0, 2, 2],
+        };
+        let directed_graph = dicomp_incidence_list_construct(&graph, 2, 2);
+        assert_eq!(neighbors(&graph, &directed_graph, 1), vec![2, 2]);
+        assert_eq!(predecessors(&graph, &directed_graph, 2), vec![1, 1]);
+    }
+
+    #[test]
+    fn dfs_visit_discover_order() {
+        let graph = EdgeList {
+            tail: vec![0, 1, 2],
+            head: vec![0, 2, 3],
+        };
+        let directed_graph = dicomp_incidence_list_construct(&graph, 3, 2);
+        let mut events = Vec::new();
+        let result = dfs_visit(&graph, &directed_graph, 3, 1, |event| {
+            events.push(event);
+            ControlFlow::Continue(())
+        });
+        assert_eq!(result, ControlFlow::Continue(()));
+        let discovered: Vec<usize> = events
+            .iter()
+            .filter_map(|&event| match event {
+                Event::Discover(v) => Some(v),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(discovered, vec![1, 2, 3]);
+        assert_eq!(
+            events,
+            vec![
+                Event::Discover(1),
+                Event::TreeEdge(1),
+                Event::Discover(2),
+                Event::TreeEdge(2),
+                Event::Discover(3),
+                Event::Finish(3),
+                Event::Finish(2),
+                Event::Finish(1),
+            ]
+        );
+    }
+
+    #[test]
+    fn dfs_visit_edge_events_and_break() {
+        let graph = EdgeList {
+            tail: vec![0, 1, 2, 3],
+            head: vec![0, 2, 3, 1],
+        };
+        let directed_graph = dicomp_incidence_list_construct(&graph, 3, 3);
+        let mut back_edges = Vec::new();
+        let _ = dfs_visit(&graph, &directed_graph, 3, 1, |event| {
+            if let Event::BackEdge(a) = event {
+                back_edges.push(a);
+            }
+            ControlFlow::Continue(())
+        });
+        assert_eq!(back_edges, vec![3]);
+
+        // 頂点2を発見したところで打ち切る
+        let mut finished = 0;
+        let result = dfs_visit(&graph, &directed_graph, 3, 1, |event| match event {
+            Event::Discover(2) => ControlFlow::Break(()),
+            Event::Finish(_) => {
+                finished += 1;
+                ControlFlow::Continue(())
+            }
+            _ => ControlFlow::Continue(()),
+        });
+        assert_eq!(result, ControlFlow::Break(()));
+        assert_eq!(finished, 0);
+    }
+
+    #[test]
+    fn find_target_main_fixture() {
+        // mainと同じグラフに孤立点7を加える
+        let graph = EdgeList {
+            tail: vec![0, 1, 1, 6, 6, 4, 5, 3, 2, 4],
+            head: vec![0, 2, 5, 2, 5, 1, 4, 6, 3, 3],
+        };
+        let directed_graph = dicomp_incidence_list_construct(&graph, 7, 9);
+        assert!(dfs_find(&graph, &directed_graph, 7, 1, 5));
+        assert!(bfs_find(&graph, &directed_graph, 7, 1, 5));
+        assert!(dfs_find(&graph, &directed_graph, 7, 3, 1));
+        assert!(bfs_find(&graph, &directed_graph, 7, 3, 1));
+        assert!(!dfs_find(&graph, &directed_graph, 7, 1, 7));
+        assert!(!bfs_find(&graph, &directed_graph, 7, 1, 7));
+        assert!(!dfs_find(&graph, &directed_graph, 7, 7, 1));
+        assert!(dfs_find(&graph, &directed_graph, 7, 7, 7));
+        assert!(bfs_find(&graph, &directed_graph, 7, 7, 7));
+    }
+
+    #[test]
+    fn from_dimacs_into_dfs() {
+        let input = "c dfs_linear\nc\np edge 3 2\ne 1 2\n\ne 2 3\n";
+        let (graph, n, m) = from_dimacs(input).unwrap();
+        assert_eq!((n, m), (3, 2));
+        assert_eq!(graph.tail, vec![0, 1, 2]);
+        assert_eq!(graph.head, vec![0, 2, 3]);
+        let directed_graph = dicomp_incidence_list_construct(&graph, n, m);
+        let result = dfs(&graph, &directed_graph, n, 1);
+        assert_eq!(result.pre_label, vec![0, 1, 2, 3]);
+        assert_eq!(result.post_label, vec![0, 3, 2, 1]);
+    }
+
+    #[test]
+    fn from_dimacs_errors() {
+        assert_eq!(
+            from_dimacs("p edge 3 2\ne 1 2\n").err(),
+            Some(ParseError::EdgeCountMismatch {
+                expected: 2,
+                found: 1
+            })
+        );
+        assert_eq!(
+            from_dimacs("e 1 2\np edge 3 1\n").err(),
+            Some(ParseError::MissingProblemLine { line: 1 })
+        );
+        assert_eq!(
+            from_dimacs("c only\n").err(),
+            Some(ParseError::MissingProblemLine { line: 2 })
+        );
+        assert_eq!(
+            from_dimacs("p edge 3 1\ne 1 4\n").err(),
+            Some(ParseError::VertexOutOfRange { line: 2, vertex: 4 })
+        );
+        assert_eq!(
+            from_dimacs("p edge 3 1\nx 1 2\n").err(),
+            Some(ParseError::UnexpectedLine { line: 2 })
+        );
+        assert_eq!(
+            from_dimacs("p col 3 1\n").err(),
+            Some(ParseError::InvalidToken {
+                line: 1,
+                token: "col".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn count_sccs_cycle_and_linear() {
+        let cycle = EdgeList {
+            tail: vec![0, 1, 2, 3],
+            head: vec![0, 2, 3, 1],
+        };
+        let directed_graph = dicomp_incidence_list_construct(&cycle, 3, 3);
+        assert_eq!(count_sccs(&cycle, &directed_graph, 3), 1);
+
+        let linear = EdgeList {
+            tail: vec![0, 1, 2],
+            head: vec![0, 2, 3],
+        };
+        let directed_graph = dicomp_incidence_list_construct(&linear, 3, 2);
+        assert_eq!(count_sccs(&linear, &directed_graph, 3), 3);
+    }
+
+    #[test]
+    fn dfs_direction_linear() {
+        let graph = EdgeList {
+            tail: vec![0, 1, 2],
+            head: vec![0, 2, 3],
+        };
+        let directed_graph = dicomp_incidence_list_construct(&graph, 3, 2);
+        let reverse = dfs_direction(&graph, &directed_graph, 3, 3, Direction::Reverse);
+        assert_eq!(reverse.pre_label, vec![0, 3, 2, 1]);
+        assert_eq!(reverse.post_label, vec![0, 1, 2, 3]);
+
+        let forward = dfs_direction(&graph, &directed_graph, 3, 1, Direction::Forward);
+        let expected = dfs_iterative(&graph, &directed_graph, 3, 1);
+        assert_eq!(forward.pre_label, expected.pre_label);
+        assert_eq!(forward.post_label, expected.post_label);
+        // 順向きでは3から先へは進めない
+        let forward = dfs_direction(&graph, &directed_graph, 3, 3, Direction::Forward);
+        assert_eq!(forward.pre_label, vec![0, 0, 0, 1]);
+    }
+
+    #[test]
+    fn topological_sort_lex_two_chains() {
+        // 1 -> 4 -> 5 と 2 -> 3 -> 6 の独立な2本の鎖
+        let graph = EdgeList {
+            tail: vec![0, 1, 4, 2, 3],
+            head: vec![0, 4, 5, 3, 6],
+        };
+        let directed_graph = dicomp_incidence_list_construct(&graph, 6, 4);
+        assert_eq!(
+            topological_sort_lex(&graph, &directed_graph, 6),
+            Some(vec![1, 2, 3, 4, 5, 6])
+        );
+        assert_eq!(
+            topological_sort_kahn(&graph, &directed_graph, 6),
+            Ok(vec![1, 2, 3, 4, 5, 6])
+        );
+    }
+
+    #[test]
+    fn topological_sort_lex_cycle() {
+        let graph = EdgeList {
+            tail: vec![0, 1, 2, 3],
+            head: vec![0, 2, 3, 1],
+        };
+        let directed_graph = dicomp_incidence_list_construct(&graph, 3, 3);
+        assert_eq!(topological_sort_lex(&graph, &directed_graph, 3), None);
+    }
+
+    #[test]
+    fn same_graph_ignores_edge_order() {
+        let a = EdgeList {
+            tail: vec![0, 1, 1, 2, 1],
+            head: vec![0, 2, 3, 3, 2],
+        };
+        let b = EdgeList {
+            tail: vec![0, 2, 1, 1, 1],
+            head: vec![0, 3, 2, 3, 2],
+        };
+        assert!(same_graph(&a, &b, 3));
+
+        // 多重辺の本数が違う
+        let c = EdgeList {
+            tail: vec![0, 2, 1, 1, 1],
+            head: vec![0, 3, 2, 3, 3],
+        };
+        assert!(!same_graph(&a, &c, 3));
+        // 向きが違う
+        let d = EdgeList {
+            tail: vec![0, 1, 1, 3, 1],
+            head: vec![0, 2, 3, 2, 2],
+        };
+        assert!(!same_graph(&a, &d, 3));
+    }
+
+    #[test]
+    fn contract_edge_triangle() {
+        let triangle = EdgeList {
+            tail: vec![0, 1, 2, 3],
+            head: vec![0, 2, 3, 1],
+        };
+        let (contracted, m) = contract_edge(&triangle, 3, 3, 1, false);
+        // 頂点2は1に、頂点3は2になる
+        assert_eq!(m, 2);
+        assert_eq!(contracted.tail, vec![0, 1, 2]);
+        assert_eq!(contracted.head, vec![0, 2, 1]);
+        assert_eq!(validate(&contracted, 2, m), Ok(()));
+    }
+
+    #[test]
+    fn contract_edge_parallel_becomes_self_loop() {
+        let graph = EdgeList {
+            tail: vec![0, 1, 2, 2],
+            head: vec![0, 3, 3, 1],
+        };
+        // 辺1 (1 -> 3) を縮約すると、辺2は 2 -> 1、辺3 (2 -> 1) はそのまま
+        let (contracted, m) = contract_edge(&graph, 3, 3, 1, true);
+        assert_eq!(m, 2);
+        assert_eq!(contracted.tail, vec![0, 2, 2]);
+        assert_eq!(contracted.head, vec![0, 1, 1]);
+
+        // 辺3 (2 -> 1) を縮約すると、辺1 と 辺2 は 1 -> 2 になり、両端を結ぶ辺は残らない
+        let (contracted, m) = contract_edge(&graph, 3, 3, 3, true);
+        assert_eq!(m, 2);
+        assert_eq!(contracted.tail, vec![0, 1, 1]);
+        assert_eq!(contracted.head, vec![0, 2, 2]);
+
+        // 多重辺 1 - 2 の片方を縮約すると、もう片方が自己ループになる
+        let multi = EdgeList {
+            tail: vec![0, 1, 1],
+            head: vec![0, 2, 2],
+        };
+        assert_eq!(contract_edge(&multi, 2, 2, 1, false).1, 1);
+        assert_eq!(contract_edge(&multi, 2, 2, 1, true).1, 0);
+    }
+
+    #[test]
+    fn min_cut_karger_two_triangles() {
+        // 三角形 1-2-3 と 4-5-6 を辺 3-4 でつなぐ
+        let graph = EdgeList {
+            tail: vec![0, 1, 2, 3, 4, 5, 6, 3],
+            head: vec![0, 2, 3, 1, 5, 6, 4, 4],
+        };
+        assert_eq!(min_cut_karger(&graph, 6, 7, 50, 7), 1);
+        // 同じ種なら1回だけの試行でも同じ結果になる
+        for seed in 1..10 {
+            assert_eq!(
+                min_cut_karger(&graph, 6, 7, 1, seed),
+                min_cut_karger(&graph, 6, 7, 1, seed)
+            );
+        }
+    }
+
+    #[test]
+    fn min_cut_karger_disconnected() {
+        let graph = EdgeList {
+            tail: vec![0, 1, 3],
+            head: vec![0, 2, 4],
+        };
+        assert_eq!(min_cut_karger(&graph, 4, 2, 5, 1), 0);
+    }
+
+    #[test]
+    fn line_graph_linear() {
+        let graph = EdgeList {
+            tail: vec![0, 1, 2],
+            head: vec![0, 2, 3],
+        };
+        let directed_graph = dicomp_incidence_list_construct(&graph, 3, 2);
+        let (line, n) = line_graph(&graph, &directed_graph, 3, 2);
+        assert_eq!(n, 2);
+        assert_eq!(line.tail, vec![0, 1]);
+        assert_eq!(line.head, vec![0, 2]);
+    }
+
+    #[test]
+    fn line_graph_cycle() {
+        let graph = EdgeList {
+            tail: vec![0, 1, 2, 3],
+            head: vec![0, 2, 3, 1],
+        };
+        let directed_graph = dicomp_incidence_list_construct(&graph, 3, 3);
+        let (line, n) = line_graph(&graph, &directed_graph, 3, 3);
+        assert_eq!(n, 3);
+        assert_eq!(line.tail, vec![0, 1, 2, 3]);
+        assert_eq!(line.head, vec![0, 2, 3, 1]);
+    }
+
+    #[test]
+    fn simple_cycles_cycle_and_dag() {
+        let cycle = EdgeList {
+            tail: vec![0, 1, 2, 3],
+            head: vec![0, 2, 3, 1],
+        };
+        let directed_graph = dicomp_incidence_list_construct(&cycle, 3, 3);
+        assert_eq!(
+            simple_cycles(&cycle, &directed_graph, 3, None),
+            vec![vec![1, 2, 3]]
+        );
+
+        let dag = EdgeList {
+            tail: vec![0, 1, 1, 2],
+            head: vec![0, 2, 3, 3],
+        };
+        let directed_graph = dicomp_incidence_list_construct(&dag, 3, 3);
+        assert!(simple_cycles(&dag, &directed_graph, 3, None).is_empty());
+    }
+
+    #[test]
+    fn simple_cycles_shared_vertices_and_cap() {
+        // 1 <-> 2, 2 -> 3 -> 1, 3の自己ループ, 1 -> 3 -> 1(多重辺 3 -> 1 を含む)
+        let graph = EdgeList {
+            tail: vec![0, 1, 2, 2, 3, 3, 1, 3],
+            head: vec![0, 2, 1, 3, 1, 3, 3, 1],
+        };
+        let directed_graph = dicomp_incidence_list_construct(&graph, 3, 7);
+        let mut cycles = simple_cycles(&graph, &directed_graph, 3, None);
+        cycles.sort();
+        assert_eq!(cycles, vec![vec![1, 2], vec![1, 2, 3], vec![1, 3], vec![3]]);
+        assert_eq!(simple_cycles(&graph, &directed_graph, 3, Some(2)).len(), 2);
+        assert!(simple_cycles(&graph, &directed_graph, 3, Some(0)).is_empty());
+    }
+
+    #[test]
+    fn simple_cycles_long_ring() {
+        // 1 -> 2 -> ... -> n -> 1 の環。閉路は全ての頂点を通る1つだけ
+        let n = 100_000;
+        let graph = EdgeList {
+            tail: (0..=n).collect(),
+            head: std::iter::once(0).chain(2..=n).chain([1]).collect(),
+        };
+        let directed_graph = dicomp_incidence_list_construct(&graph, n, n);
+        let cycles = simple_cycles(&graph, &directed_graph, n, None);
+        assert_eq!(cycles.len(), 1);
+        assert_eq!(cycles[0], (1..=n).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn edges_multi_edges_same_pair() {
+        let graph = EdgeList {
+            tail: vec![0, 1, 1],
+            head: vec![0, 2, 2],
+        };
+        assert_eq!(edges(&graph, 2).collect::<Vec<_>>(), vec![(1, 2), (1, 2)]);
+        assert_eq!(edges(&graph, 0).count(), 0);
+
+        let text = "1 2\n2 3\n";
+        let (graph, m) = from_edge_text(text, 3).unwrap();
+        assert_eq!(edges(&graph, m).collect::<Vec<_>>(), vec![(1, 2), (2, 3)]);
+    }
+
+    #[test]
+    fn reachable_subgraph_disconnected() {
+        let graph = EdgeList {
+            tail: vec![0, 1, 3],
+            head: vec![0, 2, 4],
+        };
+        let directed_graph = dicomp_incidence_list_construct(&graph, 4, 2);
+        let (sub, n, original) = reachable_subgraph(&graph, &directed_graph, 4, 2, 1);
+        assert_eq!(n, 2);
+        assert_eq!(original, vec![0, 1, 2]);
+        assert_eq!(sub.tail, vec![0, 1]);
+        assert_eq!(sub.head, vec![0, 2]);
+
+        // 3から辿ると番号が付け直される
+        let (sub, n, original) = reachable_subgraph(&graph, &directed_graph, 4, 2, 3);
+        assert_eq!(n, 2);
+        assert_eq!(original, vec![0, 3, 4]);
+        assert_eq!(sub.tail, vec![0, 1]);
+        assert_eq!(sub.head, vec![0, 2]);
+    }
+
+    #[test]
+    fn scc_dag_layers_cycle() {
+        let graph = EdgeList {
+            tail: vec![0, 1, 2, 3],
+            head: vec![0, 2, 3, 1],
+        };
+        let directed_graph = dicomp_incidence_list_construct(&graph, 3, 3);
+        assert_eq!(
+            scc_dag_layers(&graph, &directed_graph, 3),
+            vec![vec![1, 2, 3]]
+        );
+    }
+
+    #[test]
+    fn scc_dag_layers_two_sccs() {
+        // {3, 4} -> {1, 2}, 5は孤立点
+        let graph = EdgeList {
+            tail: vec![0, 1, 2, 3, 4, 4],
+            head: vec![0, 2, 1, 4, 3, 1],
+        };
+        let directed_graph = dicomp_incidence_list_construct(&graph, 5, 5);
+        assert_eq!(
+            scc_dag_layers(&graph, &directed_graph, 5),
+            vec![vec![3, 4, 5], vec![1, 2]]
+        );
+    }
+
+    #[test]
+    fn edge_list_builder_branching() {
+        let (graph, m) = EdgeListBuilder::new().edge(1, 2).edge(1, 3).finish();
+        assert_eq!(m, 2);
+        assert_eq!(
+            graph,
+            EdgeList {
+                tail: vec![0, 1, 1],
+                head: vec![0, 2, 3],
+            }
+        );
+        let (empty, m) = EdgeListBuilder::new().finish();
+        assert_eq!(m, 0);
+        assert_eq!(empty.tail, vec![0]);
+    }
+
+    #[test]
+    fn clone_and_compare_graphs() {
+        let (graph, m) = EdgeListBuilder::new().edge(1, 2).edge(2, 3).finish();
+        let directed_graph = dicomp_incidence_list_construct(&graph, 3, m);
+
+        let mut copy = graph.clone();
+        let mut directed_copy = directed_graph.clone();
+        add_edge_to_graph(&mut directed_copy, &mut copy, 3, 1);
+        assert_ne!(copy, graph);
+        assert_ne!(directed_copy, directed_graph);
+        assert_eq!(graph.tail, vec![0, 1, 2]);
+
+        let time = dfs(&graph, &directed_graph, 3, 1);
+        assert_eq!(time, dfs_iterative(&graph, &directed_graph, 3, 1));
+        assert_eq!(time.clone(), time);
+    }
+
+    #[test]
+    fn floyd_warshall_four_vertices() {
+        // 1 -> 3 (-2), 3 -> 4 (2), 4 -> 2 (-1), 2 -> 1 (4), 2 -> 3 (3)
+        let graph = EdgeList {
+            tail: vec![0, 1, 3, 4, 2, 2],
+            head: vec![0, 3, 4, 2, 1, 3],
+        };
+        let weight = [0, -2, 2, -1, 4, 3];
+        let expected = vec![
+            vec![0, -1, -2, 0],
+            vec![4, 0, 2, 4],
+            vec![5, 1, 0, 2],
+            vec![3, -1, 1, 0],
+        ];
+        assert_eq!(floyd_warshall_edges(&graph, &weight, 4, 5), expected);
+
+        let x = i64::MAX;
+        let matrix = vec![
+            vec![0, x, -2, x],
+            vec![4, 0, 3, x],
+            vec![x, x, 0, 2],
+            vec![x, -1, x, 0],
+        ];
+        assert_eq!(floyd_warshall(&matrix, 4), expected);
+    }
+
+    #[test]
+    fn floyd_warshall_unreachable_and_negative_cycle() {
+        let graph = EdgeList {
+            tail: vec![0, 1, 2, 2],
+            head: vec![0, 2, 1, 1],
+        };
+        let dist = floyd_warshall_edges(&graph, &[0, 1, 5, -3], 3, 3);
+        // 2 -> 1 は重みの小さい辺3を使うので、1 -> 2 -> 1 は負閉路
+        assert!(dist[0][0] < 0);
+        assert!(dist[1][1] < 0);
+        assert_eq!(dist[2][2], 0);
+        assert_eq!(dist[0][2], i64::MAX);
+        assert_eq!(dist[2][0], i64::MAX);
+    }
+
+    #[test]
+    fn topological_rank_linear() {
+        let graph = EdgeList {
+            tail: vec![0, 1, 2],
+            head: vec![0, 2, 3],
+        };
+        let directed_graph = dicomp_incidence_list_construct(&graph, 3, 2);
+        let rank = topological_rank(&graph, &directed_graph, 3).unwrap();
+        assert!(rank[1] < rank[2] && rank[2] < rank[3]);
+        assert_eq!(rank, vec![0, 1, 2, 3]);
+
+        let cycle = EdgeList {
+            tail: vec![0, 1, 2, 3],
+            head: vec![0, 2, 3, 1],
+        };
+        let directed_graph = dicomp_incidence_list_construct(&cycle, 3, 3);
+        assert_eq!(topological_rank(&cycle, &directed_graph, 3), None);
+    }
+
+    #[test]
+    fn same_scc_cycle_and_linear() {
+        let cycle = EdgeList {
+            tail: vec![0, 1, 2, 3],
+            head: vec![0, 2, 3, 1],
+        };
+        let directed_graph = dicomp_incidence_list_construct(&cycle, 3, 3);
+        assert!(same_scc(&cycle, &directed_graph, 3, 1, 3));
+        assert!(same_scc(&cycle, &directed_graph, 3, 2, 1));
+
+        let linear = EdgeList {
+            tail: vec![0, 1, 2],
+            head: vec![0, 2, 3],
+        };
+        let directed_graph = dicomp_incidence_list_construct(&linear, 3, 2);
+        assert!(!same_scc(&linear, &directed_graph, 3, 1, 3));
+        assert!(!same_scc(&linear, &directed_graph, 3, 3, 1));
+        assert!(same_scc(&linear, &directed_graph, 3, 2, 2));
+    }
+
+    #[test]
+    fn complement_single_edge() {
+        let graph = EdgeList {
+            tail: vec![0, 1],
+            head: vec![0, 2],
+        };
+        let (result, m) = complement(&graph, 3, 1);
+        assert_eq!(m, 5);
+        assert_eq!(
+            edges(&result, m).collect::<Vec<_>>(),
+            vec![(1, 3), (2, 1), (2, 3), (3, 1), (3, 2)]
+        );
+
+        // 補グラフの補グラフは元のグラフ(自己ループを除く)に戻る
+        let (back, m) = complement(&result, 3, m);
+        assert!(same_graph(&back, &graph, 3));
+        assert_eq!(m, 1);
+    }
+
+    #[test]
+    fn try_construct_capacity_overflow() {
+        let graph = EdgeList {
+            tail: vec![0],
+            head: vec![0],
+        };
+        assert_eq!(
+            try_construct(&graph, usize::MAX, 0).err(),
+            Some(GraphError::CapacityOverflow)
+        );
+        assert_eq!(
+            try_construct(&graph, 1, usize::MAX).err(),
+            Some(GraphError::CapacityOverflow)
+        );
+        // n + 1 はusizeに収まるが、Vec<usize>としては確保できない
+        assert_eq!(
+            try_construct(&graph, usize::MAX - 1, 0).err(),
+            Some(GraphError::CapacityOverflow)
+        );
+        assert_eq!(
+            validate(&graph, MAX_ARRAY_LEN, 0),
+            Err(GraphError::CapacityOverflow)
+        );
+        // 境界: n + 1 == MAX_ARRAY_LEN は検査を通る(確保はしない)
+        assert_eq!(validate(&graph, MAX_ARRAY_LEN - 1, 0), Ok(()));
+    }
+
+    #[test]
+    fn ancestors_linear_and_cycle() {
+        let graph = EdgeList {
+            tail: vec![0, 1, 2],
+            head: vec![0, 2, 3],
+        };
+        let directed_graph = dicomp_incidence_list_construct(&graph, 3, 2);
+        assert_eq!(ancestors(&graph, &directed_graph, 3, 3), vec![1, 2, 3]);
+        assert_eq!(ancestors(&graph, &directed_graph, 3, 1), vec![1]);
+
+        // 閉路と自己ループがあっても止まる
+        let graph = EdgeList {
+            tail: vec![0, 1, 2, 3, 2],
+            head: vec![0, 2, 3, 1, 2],
+        };
+        let directed_graph = dicomp_incidence_list_construct(&graph, 4, 4);
+        assert_eq!(ancestors(&graph, &directed_graph, 4, 2), vec![1, 2, 3]);
+        assert_eq!(ancestors(&graph, &directed_graph, 4, 4), vec![4]);
+    }
+
+    #[test]
+    fn descendants_match_dfs_labels() {
+        let graph = EdgeList {
+            tail: vec![0, 1, 1, 2, 6, 4, 5, 3, 2, 3],
+            head: vec![0, 2, 5, 6, 5, 1, 4, 6, 3, 4],
+        };
+        let directed_graph = dicomp_incidence_list_construct(&graph, 6, 9);
+        for v in 1..=6 {
+            let time = dfs(&graph, &directed_graph, 6, v);
+            let labelled: Vec<usize> = (1..=6).filter(|&w| time.pre_label[w] != 0).collect();
+            assert_eq!(descendants(&graph, &directed_graph, 6, v), labelled);
+        }
+
+        let linear = EdgeList {
+            tail: vec![0, 1, 2],
+            head: vec![0, 2, 3],
+        };
+        let directed_graph = dicomp_incidence_list_construct(&linear, 3, 2);
+        assert_eq!(descendants(&linear, &directed_graph, 3, 2), vec![2, 3]);
+    }
+
+    #[test]
+    fn cut_size_linear() {
+        let graph = EdgeList {
+            tail: vec![0, 1, 2],
+            head: vec![0, 2, 3],
+        };
+        assert_eq!(cut_size(&graph, 3, 2, &[1]), 1);
+        assert_eq!(cut_size(&graph, 3, 2, &[1, 2]), 1);
+        assert_eq!(cut_size(&graph, 3, 2, &[1, 3]), 1);
+        assert_eq!(cut_size(&graph, 3, 2, &[3]), 0);
+        assert_eq!(cut_size(&graph, 3, 2, &[]), 0);
+        assert_eq!(cut_size(&graph, 3, 2, &[1, 2, 3]), 0);
+    }
+
+    #[test]
+    fn dfs_forest_ordered_changes_labels_not_partition() {
+        // 向きを無視すると {1, 2}, {3, 4} の2つの成分(両向きの辺を持つ)
+        let graph = EdgeList {
+            tail: vec![0, 1, 2, 3, 4],
+            head: vec![0, 2, 1, 4, 3],
+        };
+        let directed_graph = dicomp_incidence_list_construct(&graph, 4, 4);
+        let (time, tree) = dfs_forest_ordered(&graph, &directed_graph, 4, &[1, 2, 3, 4]);
+        assert_eq!(tree, vec![0, 1, 1, 2, 2]);
+        assert_eq!(time, dfs_forest(&graph, &directed_graph, 4));
+
+        let (time, reversed) = dfs_forest_ordered(&graph, &directed_graph, 4, &[4, 3, 2, 1]);
+        assert_eq!(reversed, vec![0, 2, 2, 1, 1]);
+        assert_eq!(time.pre_label, vec![0, 4, 3, 2, 1]);
+        assert!(same_partition(&tree, &reversed));
+
+        // 始点の候補に入らない頂点は訪問しない
+        let (_, partial) = dfs_forest_ordered(&graph, &directed_graph, 4, &[3]);
+        assert_eq!(partial, vec![0, 0, 0, 1, 1]);
+    }
+
+    #[test]
+    fn dfs_forest_ordered_kosaraju() {
+        // mainと同じグラフで、転置グラフを後行順ラベルの大きい順に探索すると木が強連結成分になる
+        let graph = EdgeList {
+            tail: vec![0, 1, 1, 6, 6, 4, 5, 3, 2, 4],
+            head: vec![0, 2, 5, 2, 5, 1, 4, 6, 3, 3],
+        };
+        let directed_graph = dicomp_incidence_list_construct(&graph, 6, 9);
+        let time = dfs_forest(&graph, &directed_graph, 6);
+        let mut order: Vec<usize> = (1..=6).collect();
+        order.sort_by_key(|&v| Reverse(time.post_label[v]));
+
+        let reversed = transpose(&graph, 9);
+        let reversed_graph = dicomp_incidence_list_construct(&reversed, 6, 9);
+        let (_, tree) = dfs_forest_ordered(&reversed, &reversed_graph, 6, &order);
+        assert!(same_partition(
+            &tree,
+            &strongly_connected_components(&graph, &directed_graph, 6)
+        ));
+    }
+
+    #[test]
+    fn dfs_tree_edges_branching() {
+        let graph = EdgeList {
+            tail: vec![0, 1, 1],
+            head: vec![0, 2, 3],
+        };
+        let directed_graph = dicomp_incidence_list_construct(&graph, 3, 2);
+        assert_eq!(dfs_tree_edges(&graph, &directed_graph, 3, 1), vec![1, 2]);
+        assert!(dfs_tree_edges(&graph, &directed_graph, 3, 2).is_empty());
+    }
+
+    #[test]
+    fn dfs_tree_edges_match_parent() {
+        let graph = EdgeList {
+            tail: vec![0, 1, 1, 2, 6, 4, 5, 3, 2, 3],
+            head: vec![0, 2, 5, 6, 5, 1, 4, 6, 3, 4],
+        };
+        let directed_graph = dicomp_incidence_list_construct(&graph, 6, 9);
+        let tree_edges = dfs_tree_edges(&graph, &directed_graph, 6, 1);
+        assert_eq!(tree_edges.len(), 5);
+        let (_, parent) = dfs_with_parent(&graph, &directed_graph, 6, 1);
+        for a in tree_edges {
+            assert_eq!(parent[graph.head[a]], graph.tail[a]);
+        }
+    }
+
+    #[test]
+    fn compact_sparse_ids() {
+        let graph = EdgeList {
+            tail: vec![0, 1, 100],
+            head: vec![0, 100, 5],
+        };
+        let (result, n, original) = compact(&graph, 2);
+        assert_eq!(n, 3);
+        assert_eq!(original, vec![0, 1, 5, 100]);
+        assert_eq!(result.tail, vec![0, 1, 3]);
+        assert_eq!(result.head, vec![0, 3, 2]);
+        assert_eq!(validate(&result, n, 2), Ok(()));
+
+        let (empty, n, original) = compact(&EdgeListBuilder::new().finish().0, 0);
+        assert_eq!((n, original), (0, vec![0]));
+        assert_eq!(empty.tail, vec![0]);
+    }
+
+    #[test]
+    fn is_tree_cycle_and_path() {
+        let cycle = EdgeList {
+            tail: vec![0, 1, 2, 3],
+            head: vec![0, 2, 3, 1],
+        };
+        let directed_graph = dicomp_incidence_list_construct(&cycle, 3, 3);
+        assert!(!is_tree(&cycle, &directed_graph, 3, 3));
+        assert!(!is_forest(&cycle, &directed_graph, 3, 3));
+
+        // 向きを無視すると 1 - 2 - 3 の路
+        let path = EdgeList {
+            tail: vec![0, 1, 3],
+            head: vec![0, 2, 2],
+        };
+        let directed_graph = dicomp_incidence_list_construct(&path, 3, 2);
+        assert!(is_tree(&path, &directed_graph, 3, 2));
+        assert!(is_forest(&path, &directed_graph, 3, 2));
+    }
+
+    #[test]
+    fn is_forest_disconnected_and_multi_edges() {
+        let graph = EdgeList {
+            tail: vec![0, 1, 3],
+            head: vec![0, 2, 4],
+        };
+        let directed_graph = dicomp_incidence_list_construct(&graph, 4, 2);
+        assert!(!is_tree(&graph, &directed_graph, 4, 2));
+        assert!(is_forest(&graph, &directed_graph, 4, 2));
+
+        // 1 -> 2 と 2 -> 1 は向きを無視すると多重辺
+        let multi = EdgeList {
+            tail: vec![0, 1, 2],
+            head: vec![0, 2, 1],
+        };
+        let directed_graph = dicomp_incidence_list_construct(&multi, 3, 2);
+        assert!(!is_tree(&multi, &directed_graph, 3, 2));
+        assert!(!is_forest(&multi, &directed_graph, 3, 2));
+
+        let self_loop = EdgeList {
+            tail: vec![0, 2],
+            head: vec![0, 2],
+        };
+        let directed_graph = dicomp_incidence_list_construct(&self_loop, 3, 1);
+        assert!(!is_forest(&self_loop, &directed_graph, 3, 1));
+
+        let single = EdgeListBuilder::new().finish().0;
+        let directed_graph = dicomp_incidence_list_construct(&single, 1, 0);
+        assert!(is_tree(&single, &directed_graph, 1, 0));
+    }
+
+    #[test]
+    fn multi_source_bfs_linear() {
+        let graph = EdgeList {
+            tail: vec![0, 1, 2],
+            head: vec![0, 2, 3],
+        };
+        let directed_graph = dicomp_incidence_list_construct(&graph, 3, 2);
+        let x = usize::MAX;
+        assert_eq!(
+            multi_source_bfs(&graph, &directed_graph, 3, &[1, 3]),
+            vec![x, 0, 1, 0]
+        );
+        assert_eq!(
+            multi_source_bfs(&graph, &directed_graph, 3, &[2, 2]),
+            vec![x, x, 0, 1]
+        );
+        assert_eq!(
+            multi_source_bfs(&graph, &directed_graph, 3, &[]),
+            vec![x, x, x, x]
+        );
+    }
+
+    #[test]
+    fn multi_source_bfs_matches_min_of_bfs() {
+        let graph = EdgeList {
+            tail: vec![0, 1, 1, 6, 6, 4, 5, 3, 2, 4],
+            head: vec![0, 2, 5, 2, 5, 1, 4, 6, 3, 3],
+        };
+        let directed_graph = dicomp_incidence_list_construct(&graph, 6, 9);
+        for sources in [vec![1], vec![3, 5], vec![2, 4, 6], vec![6, 6, 2]] {
+            let expected: Vec<usize> = (0..=6)
+                .map(|v| {
+                    sources
+                        .iter()
+                        .map(|&s| bfs(&graph, &directed_graph, 6, s)[v])
+                        .min()
+                        .unwrap()
+                })
+                .collect();
+            assert_eq!(
+                multi_source_bfs(&graph, &directed_graph, 6, &sources),
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn topo_iter_linear() {
+        let graph = EdgeList {
+            tail: vec![0, 1, 2],
+            head: vec![0, 2, 3],
+        };
+        let directed_graph = dicomp_incidence_list_construct(&graph, 3, 2);
+        let order: Result<Vec<usize>, CycleError> = topo_iter(&graph, &directed_graph, 3).collect();
+        assert_eq!(order, Ok(vec![1, 2, 3]));
+    }
+
+    #[test]
+    fn topo_iter_cycle() {
+        // 1 -> 2 -> 3 -> 2。1だけ取り出せる
+        let graph = EdgeList {
+            tail: vec![0, 1, 2, 3],
+            head: vec![0, 2, 3, 2],
+        };
+        let directed_graph = dicomp_incidence_list_construct(&graph, 3, 3);
+        let mut iter = topo_iter(&graph, &directed_graph, 3);
+        assert_eq!(iter.next(), Some(Ok(1)));
+        assert_eq!(iter.next(), Some(Err(CycleError { remaining: 2 })));
+        assert_eq!(iter.next(), None);
+        assert_eq!(iter.next(), None);
+    }
+
+    #[test]
+    fn zero_one_bfs_matches_dijkstra() {
+        let graph = EdgeList {
+            tail: vec![0, 1, 1, 6, 6, 4, 5, 3, 2, 4],
+            head: vec![0, 2, 5, 2, 5, 1, 4, 6, 3, 3],
+        };
+        let directed_graph = dicomp_incidence_list_construct(&graph, 7, 9);
+        let weight = [0, 1, 0, 0, 1, 1, 0, 1, 1, 0];
+        for source in 1..=7 {
+            assert_eq!(
+                zero_one_bfs(&graph, &directed_graph, &weight, 7, source),
+                dijkstra(&graph, &directed_graph, &weight, 7, source)
+            );
+        }
+        assert_eq!(
+            zero_one_bfs(&graph, &directed_graph, &weight, 7, 1),
+            vec![i64::MAX, 0, 1, 0, 0, 0, 1, i64::MAX]
+        );
+    }
+
+    #[test]
+    fn dial_bfs_matches_dijkstra() {
+        let graph = EdgeList {
+            tail: vec![0, 1, 1, 6, 6, 4, 5, 3, 2, 4],
+            head: vec![0, 2, 5, 2, 5, 1, 4, 6, 3, 3],
+        };
+        let directed_graph = dicomp_incidence_list_construct(&graph, 7, 9);
+        let weight = [0, 3, 0, 2, 1, 3, 0, 1, 2, 3];
+        for source in 1..=7 {
+            assert_eq!(
+                dial_bfs(&graph, &directed_graph, &weight, 7, source, 3),
+                dijkstra(&graph, &directed_graph, &weight, 7, source)
+            );
+        }
+        let unit = [0, 1, 1, 1, 1, 1, 1, 1, 1, 1];
+        let dist = dial_bfs(&graph, &directed_graph, &unit, 7, 1, 1);
+        let hops = bfs(&graph, &directed_graph, 7, 1);
+        for v in 1..=7 {
+            assert_eq!(dist[v] == i64::MAX, hops[v] == usize::MAX);
+            if hops[v] != usize::MAX {
+                assert_eq!(dist[v], hops[v] as i64);
+            }
+        }
+    }
+
+    #[test]
+    fn reachable_count_disconnected() {
+        let graph = EdgeList {
+            tail: vec![0, 1, 3],
+            head: vec![0, 2, 4],
+        };
+        let directed_graph = dicomp_incidence_list_construct(&graph, 4, 2);
+        assert_eq!(reachable_count(&graph, &directed_graph, 4, 1), 2);
+        assert_eq!(reachable_count(&graph, &directed_graph, 4, 2), 1);
+        for v in 1..=4 {
+            assert_eq!(
+                reachable_count(&graph, &directed_graph, 4, v),
+                descendants(&graph, &directed_graph, 4, v).len()
+            );
+        }
+    }
+
+    #[test]
+    fn betweenness_centrality_path_and_star() {
+        // 1 - 2 - 3 の路(両向きの辺)
+        let path = EdgeList {
+            tail: vec![0, 1, 2, 2, 3],
+            head: vec![0, 2, 1, 3, 2],
+        };
+        let directed_graph = dicomp_incidence_list_construct(&path, 3, 4);
+        assert_eq!(
+            betweenness_centrality(&path, &directed_graph, 3),
+            vec![0.0, 0.0, 2.0, 0.0]
+        );
+
+        // 中心1と葉2..=5の星(両向きの辺)。葉の順序対 4 * 3 = 12 組が全て中心を通る
+        let (star, m) = EdgeListBuilder::new()
+            .edge(1, 2)
+            .edge(2, 1)
+            .edge(1, 3)
+            .edge(3, 1)
+            .edge(1, 4)
+            .edge(4, 1)
+            .edge(1, 5)
+            .edge(5, 1)
+            .finish();
+        let directed_graph = dicomp_incidence_list_construct(&star, 5, m);
+        assert_eq!(
+            betweenness_centrality(&star, &directed_graph, 5),
+            vec![0.0, 12.0, 0.0, 0.0, 0.0, 0.0]
+        );
+    }
+
+    #[test]
+    fn betweenness_centrality_splits_equal_paths() {
+        // 1 -> 2 -> 4 と 1 -> 3 -> 4 の2本の最短路。2と3が半分ずつ受け持つ
+        let graph = EdgeList {
+            tail: vec![0, 1, 1, 2, 3],
+            head: vec![0, 2, 3, 4, 4],
+        };
+        let directed_graph = dicomp_incidence_list_construct(&graph, 4, 4);
+        assert_eq!(
+            betweenness_centrality(&graph, &directed_graph, 4),
+            vec![0.0, 0.0, 0.5, 0.5, 0.0]
+        );
+    }
+
+    #[test]
+    fn closeness_centrality_path() {
+        // 1 - 2 - 3 の路(両向きの辺)
+        let path = EdgeList {
+            tail: vec![0, 1, 2, 2, 3],
+            head: vec![0, 2, 1, 3, 2],
+        };
+        let directed_graph = dicomp_incidence_list_construct(&path, 3, 4);
+        let c = closeness_centrality(&path, &directed_graph, 3);
+        assert_eq!(c[2], 1.0);
+        assert!((c[1] - 2.0 / 3.0).abs() < 1e-12);
+        assert_eq!(c[1], c[3]);
+        assert!(c[2] > c[1]);
+    }
+
+    #[test]
+    fn closeness_centrality_partial_reach() {
+        let linear = EdgeList {
+            tail: vec![0, 1, 2],
+            head: vec![0, 2, 3],
+        };
+        let directed_graph = dicomp_incidence_list_construct(&linear, 3, 2);
+        let c = closeness_centrality(&linear, &directed_graph, 3);
+        // 1からは2, 3に距離1, 2で届く。2からは3だけに届くので半分に縮める
+        assert!((c[1] - 2.0 / 3.0).abs() < 1e-12);
+        assert_eq!(c[2], 0.5);
+        assert_eq!(c[3], 0.0);
     }
 }
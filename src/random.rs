@@ -0,0 +1,104 @@
+use super::EdgeList;
+
+/// 種から再現できる小さな擬似乱数生成器(xorshift64*)
+/// 外部のクレートに頼らずに、同じ種なら常に同じ列を返す
+pub(crate) struct XorShift {
+    state: u64,
+}
+
+impl XorShift {
+    /// 種seedから作る(状態が0だと0しか出なくなるので、0のときは別の定数を使う)
+    pub(crate) fn new(seed: u64) -> Self {
+        XorShift {
+            state: if seed == 0 {
+                0x9E37_79B9_7F4A_7C15
+            } else {
+                seed
+            },
+        }
+    }
+
+    pub(crate) fn next_u64(&mut self) -> u64 {
+        let mut x = self.state;
+        x ^= x >> 12;
+        x ^= x << 25;
+        x ^= x >> 27;
+        self.state = x;
+        x.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+
+    /// [0, 1) の一様乱数
+    pub(crate) fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// 0..bound の一様乱数(bound > 0)
+    pub(crate) fn below(&mut self, bound: usize) -> usize {
+        (self.next_u64() % bound as u64) as usize
+    }
+}
+
+/// Erdős–RényiのG(n, p)モデルによるランダムな有向グラフ
+/// 順序対 (u, v) ごとに確率pで辺 u -> v を加える
+/// self_loops が false なら u == v の対は飛ばす
+/// 辺の番号は (u, v) の小さい順に付ける
+/// 返り値は辺リストと辺数m
+/// n: 頂点数
+/// seed: 乱数の種(同じ種なら同じグラフになる)
+pub(crate) fn gnp(n: usize, p: f64, seed: u64, self_loops: bool) -> (EdgeList, usize) {
+    let mut rng = XorShift::new(seed);
+    let mut edge = EdgeList {
+        tail: vec![0],
+        head: vec![0],
+    };
+    for u in 1..=n {
+        for v in 1..=n {
+            if u == v && !self_loops {
+                continue;
+            }
+            if rng.next_f64() < p {
+                edge.tail.push(u);
+                edge.head.push(v);
+            }
+        }
+    }
+    let m = edge.tail.len() - 1;
+    (edge, m)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gnp_is_deterministic() {
+        let (a, ma) = gnp(30, 0.2, 42, false);
+        let (b, mb) = gnp(30, 0.2, 42, false);
+        assert_eq!(ma, mb);
+        assert_eq!(a.tail, b.tail);
+        assert_eq!(a.head, b.head);
+
+        let (c, _) = gnp(30, 0.2, 43, false);
+        assert!(a.tail != c.tail || a.head != c.head);
+    }
+
+    #[test]
+    fn gnp_extremes_and_self_loops() {
+        let (_, m) = gnp(10, 0.0, 1, true);
+        assert_eq!(m, 0);
+
+        let (edge, m) = gnp(10, 1.0, 1, false);
+        assert_eq!(m, 90);
+        assert!((1..=m).all(|a| edge.tail[a] != edge.head[a]));
+
+        let (_, m) = gnp(10, 1.0, 1, true);
+        assert_eq!(m, 100);
+    }
+
+    #[test]
+    fn gnp_density() {
+        let (_, m) = gnp(100, 0.3, 7, false);
+        // 期待値は 9900 * 0.3 = 2970
+        assert!((2700..3250).contains(&m));
+    }
+}